@@ -0,0 +1,857 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use leptos::*;
+use leptos::CollectView; 
+use gloo_timers::callback::Interval;
+use gloo_timers::future::TimeoutFuture;
+use uuid::Uuid;
+use web_sys::HtmlInputElement;
+
+use crate::assets::AssetRegistry;
+use crate::audit::{self, AuditEntry, AuditError, AuditStore, ChainHead, IndexedDbAuditStore, MemoryAuditStore};
+use crate::attestation::{AttestationBody, IntentAttestation, CURRENT_SCHEMA_VERSION, SIGNING_CONTEXT};
+use crate::biometric::{BiometricAssertion, BiometricError, BiometricProvider, WebAuthnProvider};
+use crate::export::{self, Bundle};
+use crate::hold::{self, HoldError, HoldTiming};
+use crate::hold_gesture::HoldGesture;
+use crate::keys::{self, DeviceKey};
+use crate::merkle::{self, Checkpoint, MerkleTree};
+use crate::oracle::{self, AggregatedPrice, FetchHttp, OracleError, OracleSet, Pair, RefreshSchedule, TickerStream};
+use crate::state::{VaultEvent, VaultState};
+use crate::verify::{self, Policy};
+use crate::verify_view::VerifyView;
+use crate::wallet::{self, WalletError, WalletHandle, WalletInfo};
+
+/* ===================== AUDIT EXPORT ===================== */
+
+#[derive(Clone, Copy)]
+enum ExportFormat {
+    JsonLines,
+    Csv,
+    /// Signed by the device key, so only while it is unsealed.
+    Bundle,
+}
+
+/* ===================== PRICES ===================== */
+
+/// The latest oracle answer for one asset; assets not yet fetched have none.
+type PriceReading = Result<AggregatedPrice, OracleError>;
+
+/// Displayed price and the flag shown beside it when it cannot be trusted.
+fn price_label(reading: Option<&PriceReading>, now_ms: u64, max_age_ms: u64) -> (String, Option<&'static str>) {
+    match reading {
+        None => ("—".into(), None),
+        Some(Err(_)) => ("—".into(), Some("NO QUOTE")),
+        Some(Ok(price)) => (price.price.clone(), price.is_stale(now_ms, max_age_ms).then_some("STALE")),
+    }
+}
+
+/// `$67012.5` for dollars, `61234.1 EUR` for anything else.
+fn with_currency(amount: &str, currency: &str) -> String {
+    match currency {
+        "USD" => format!("${}", amount),
+        other => format!("{} {}", amount, other),
+    }
+}
+
+/// Hover text: which sources made up the price, or why there is none.
+fn price_detail(reading: Option<&PriceReading>) -> String {
+    match reading {
+        None => "FETCHING".into(),
+        Some(Err(e)) => format!("ORACLE ERROR: {}", e).to_uppercase(),
+        Some(Ok(price)) => {
+            let used: Vec<&str> = price.quotes.iter().map(|q| q.source.as_str()).collect();
+            let dropped: Vec<&str> = price.rejected.iter().map(|(source, _)| source.as_str()).collect();
+            let mut detail = format!("MEDIAN OF {}", used.join(", ")).to_uppercase();
+            if !dropped.is_empty() {
+                detail.push_str(&format!(" · DROPPED {}", dropped.join(", ")).to_uppercase());
+            }
+            detail
+        }
+    }
+}
+
+/* ===================== VEXT VAULT APP ===================== */
+
+#[component]
+pub fn App(
+    /// Shortest hold-to-authorize accepted as intent, in milliseconds.
+    #[prop(default = hold::DEFAULT_MIN_HOLD_MS)]
+    min_hold_ms: u64,
+    /// User-verification source; defaults to the WebAuthn platform authenticator.
+    #[prop(optional)]
+    biometric: Option<Rc<dyn BiometricProvider>>,
+    /// Price polling interval while the oracles answer, in milliseconds.
+    #[prop(default = oracle::DEFAULT_REFRESH_MS)]
+    price_refresh_ms: u64,
+    /// Also follow the Coinbase ticker feed for live prices between polls.
+    #[prop(optional)]
+    stream_prices: bool,
+    /// The assets on offer; BTC, ETH and SOL against USD unless configured.
+    #[prop(optional)]
+    assets: AssetRegistry,
+    /// Where sealed attestations are kept; defaults to the origin's IndexedDB.
+    #[prop(optional)]
+    audit_store: Option<Rc<dyn AuditStore>>,
+) -> impl IntoView {
+    // --- STATE SIGNALS ---
+    let (vault, set_vault) = create_signal(VaultState::default());
+    let device_key = store_value(None::<DeviceKey>);
+    let biometric = store_value(biometric.or_else(|| {
+        WebAuthnProvider::for_current_origin().ok().map(|p| Rc::new(p) as Rc<dyn BiometricProvider>)
+    }));
+    let (sealing, set_sealing) = create_signal(false);
+    // Credential that passed the Vector 2 scan; every seal this session must come from it.
+    let session_credential = store_value(None::<String>);
+    let (wallets, set_wallets) = create_signal(Vec::<WalletInfo>::new());
+    let wallet_registry = store_value(wallet::discover(move |found| set_wallets.set(found)));
+    let linked_wallet = store_value(None::<WalletHandle>);
+    let (passphrase, set_passphrase) = create_signal(String::new());
+    // Second entry, only asked for when no sealed key exists yet.
+    let (passphrase_confirm, set_passphrase_confirm) = create_signal(String::new());
+    let (status_msg, set_status_msg) = create_signal(
+        if keys::has_stored_key() { "SYSTEM READY. UNSEAL DEVICE KEY." } else { "SYSTEM READY. FORGE DEVICE KEY." }.to_string()
+    );
+    let (receipt, set_receipt) = create_signal(None::<IntentAttestation>);
+    // The file verifier replaces the vault view while open; vault state is untouched.
+    let (verifying, set_verifying) = create_signal(false);
+    let (receipt_leaf, set_receipt_leaf) = create_signal(None::<u64>);
+    let audit_store = store_value(
+        audit_store.unwrap_or_else(|| Rc::new(IndexedDbAuditStore::new(audit::AUDIT_DB_NAME)) as Rc<dyn AuditStore>),
+    );
+    // The loaded part of the log, newest first, plus the cursor for the next older page.
+    let (audit_log, set_audit_log) = create_signal(Vec::<AuditEntry>::new());
+    let (audit_next, set_audit_next) = create_signal(None::<u64>);
+    let (audit_total, set_audit_total) = create_signal(0u64);
+    let (audit_persistent, set_audit_persistent) = create_signal(true);
+    // Integrity of the whole stored chain; `None` while it is being checked.
+    let (chain, set_chain) = create_signal(None::<Result<ChainHead, AuditError>>);
+    // Every logged attestation as a Merkle leaf (leaf index = seq - 1), and the checkpoints signed over it.
+    // Checkpoints outlive the session so a later export can still prove against them.
+    let tree = store_value(MerkleTree::new());
+    let (checkpoints, set_checkpoints) = create_signal(merkle::stored_checkpoints());
+
+    let (asset, set_asset) = create_signal(assets.first().symbol.clone());
+    let assets = store_value(assets);
+    let (prices, set_prices) = create_signal(HashMap::<String, PriceReading>::new());
+    let oracles = store_value(Rc::new(OracleSet::public(Rc::new(FetchHttp))));
+    let max_quote_age_ms = oracles.with_value(|o| o.policy.max_age_ms);
+    // Ticks so a price that stops updating is flagged stale without a refetch.
+    let (clock_ms, set_clock_ms) = create_signal(js_sys::Date::now() as u64);
+    let clock = Interval::new(1_000, move || set_clock_ms.set(js_sys::Date::now() as u64));
+    on_cleanup(move || drop(clock));
+    let price = move |symbol: &str| prices.with(|p| price_label(p.get(symbol), clock_ms.get(), max_quote_age_ms));
+
+    // Every flow change goes through the state machine; invalid events are dropped.
+    let dispatch = move |event: VaultEvent| {
+        let mut applied = false;
+        set_vault.update(|state| applied = state.apply(event).is_ok());
+        applied
+    };
+    let unlocked = move || vault.with(VaultState::unlocked);
+
+    // --- AUDIT LOG: HISTORY ---
+    let load_audit_page = move |before: Option<u64>| {
+        let store = audit_store.get_value();
+        spawn_local(async move {
+            match store.page(before, audit::DEFAULT_PAGE_SIZE).await {
+                Ok(page) => {
+                    set_audit_log.update(|log| log.extend(page.entries));
+                    set_audit_next.set(page.next);
+                }
+                Err(e) => set_status_msg.set(format!("ERROR: {}.", e).to_uppercase()),
+            }
+        });
+    };
+    // Brings the tree up to date with `entries`; tree.sync skips what is
+    // already covered, so rebuilds and appends may land in any order.
+    let sync_tree = move |entries: &[AuditEntry]| -> Option<u64> {
+        match tree.try_update_value(|tree| tree.sync(entries))? {
+            Ok(size) => Some(size),
+            Err(e) => {
+                set_status_msg.set(format!("ERROR: MERKLE TREE: {}.", e).to_uppercase());
+                None
+            }
+        }
+    };
+    let rebuild_tree = move || {
+        spawn_local(async move {
+            match audit::read_all(audit_store.get_value().as_ref()).await {
+                Ok(entries) => {
+                    sync_tree(&entries);
+                    set_chain.set(Some(audit::verify_chain(&entries).map_err(AuditError::BrokenChain)));
+                }
+                Err(e) => set_chain.set(Some(Err(e))),
+            }
+        });
+    };
+    spawn_local(async move {
+        match audit_store.get_value().count().await {
+            Ok(total) => {
+                set_audit_total.set(total);
+                load_audit_page(None);
+                rebuild_tree();
+            }
+            // Without IndexedDB the log still works, but only for this session.
+            Err(AuditError::Unavailable) => {
+                audit_store.set_value(Rc::new(MemoryAuditStore::new()));
+                set_audit_persistent.set(false);
+                set_chain.set(Some(Ok(ChainHead::genesis())));
+            }
+            Err(e) => set_status_msg.set(format!("ERROR: {}.", e).to_uppercase()),
+        }
+    });
+
+    // --- AUDIT LOG: CHECKPOINTS ---
+    // Once the device key is unsealed, sign the tree whenever it has grown and the interval has passed.
+    let checkpoint_timer = Interval::new(60_000, move || {
+        let now_ms = js_sys::Date::now() as u64;
+        let last = checkpoints.with_untracked(|c| c.last().map(|s| s.checkpoint.clone()));
+        let size = tree.with_value(MerkleTree::size);
+        if !Checkpoint::due(last.as_ref(), size, now_ms, merkle::DEFAULT_CHECKPOINT_INTERVAL_MS) { return; }
+        let origin = window().location().origin().unwrap_or_default();
+        let checkpoint = tree.with_value(|tree| tree.checkpoint(&origin, now_ms));
+        if let Some(Ok(signed)) = device_key.with_value(|key| key.as_ref().map(|k| checkpoint.sign(k))) {
+            set_checkpoints.update(|c| {
+                c.push(signed);
+                merkle::store_checkpoints(c);
+            });
+        }
+    });
+    on_cleanup(move || drop(checkpoint_timer));
+
+    // --- AUDIT LOG: EXPORT ---
+    // Exports always cover the whole log, oldest first, not just the loaded pages.
+    let export_log = move |format: ExportFormat| {
+        spawn_local(async move {
+            let entries = match audit::read_all(audit_store.get_value().as_ref()).await {
+                Ok(entries) => entries,
+                Err(e) => return set_status_msg.set(format!("ERROR: {}.", e).to_uppercase()),
+            };
+            let now_ms = js_sys::Date::now() as u64;
+            let file = match format {
+                ExportFormat::JsonLines => export::to_jsonl(&entries)
+                    .map(|jsonl| ("jsonl", "application/x-ndjson", jsonl))
+                    .map_err(|e| e.to_string()),
+                ExportFormat::Csv => Ok(("csv", "text/csv", export::to_csv(&entries))),
+                ExportFormat::Bundle => {
+                    let origin = window().location().origin().unwrap_or_default();
+                    let checkpoint = checkpoints.with_untracked(|c| c.last().cloned());
+                    match device_key.with_value(|key| key.as_ref().map(|k| Bundle::create(entries, checkpoint, &origin, now_ms, k))) {
+                        None => Err("device key sealed".to_string()),
+                        Some(bundle) => bundle
+                            .map_err(|e| e.to_string())
+                            .and_then(|b| serde_json::to_string_pretty(&b).map_err(|e| e.to_string()))
+                            .map(|json| ("bundle.json", "application/json", json)),
+                    }
+                }
+            };
+            match file.and_then(|(ext, mime, contents)| export::download(&format!("vext-audit-{}.{}", now_ms, ext), mime, &contents)) {
+                Ok(()) => set_status_msg.set("AUDIT LOG EXPORTED.".into()),
+                Err(e) => set_status_msg.set(format!("ERROR: EXPORT FAILED: {}.", e).to_uppercase()),
+            }
+        });
+    };
+
+    // --- HANDLER: UNLINK WALLET ---
+    let unlink_wallet = move |_| {
+        if let Some(handle) = linked_wallet.get_value() {
+            spawn_local(async move {
+                let _ = handle.disconnect().await;
+            });
+        }
+        linked_wallet.set_value(None);
+        session_credential.set_value(None);
+        wallet::remember_wallet(None);
+        if dispatch(VaultEvent::WalletUnlinked) {
+            set_status_msg.set("WALLET UNLINKED. WAITING FOR VECTOR 1.".into());
+        }
+    };
+
+    // --- PRICE ORACLE: REFRESH LOOP & STREAM ---
+    let pairs: Vec<Pair> = assets.with_value(|a| a.iter().map(|asset| asset.pair()).collect());
+    let mounted = Rc::new(Cell::new(true));
+    on_cleanup({
+        let mounted = mounted.clone();
+        move || mounted.set(false)
+    });
+    for pair in pairs.iter().cloned() {
+        let (oracles, mounted) = (oracles.get_value(), mounted.clone());
+        spawn_local(async move {
+            let mut schedule = RefreshSchedule::new(price_refresh_ms, oracle::DEFAULT_MAX_BACKOFF_MS);
+            while mounted.get() {
+                let reading = oracles.price(&pair, js_sys::Date::now() as u64).await;
+                let delay_ms = schedule.next_delay_ms(reading.is_ok());
+                if !mounted.get() { break; }
+                // A failed refresh keeps the last good price on screen; it turns stale on its own.
+                set_prices.update(|map| {
+                    if reading.is_ok() || !matches!(map.get(&pair.symbol), Some(Ok(_))) {
+                        map.insert(pair.symbol.clone(), reading);
+                    }
+                });
+                TimeoutFuture::new(delay_ms.min(u32::MAX as u64) as u32).await;
+            }
+        });
+    }
+    if stream_prices {
+        let policy = oracles.with_value(|o| o.policy.clone());
+        let stream = TickerStream::coinbase(pairs, move |quote| {
+            set_prices.update(|map| {
+                if let Some(Ok(price)) = map.get_mut(&quote.symbol) {
+                    if let Ok(next) = price.with_quote(quote, js_sys::Date::now() as u64, &policy) {
+                        *price = next;
+                    }
+                }
+            });
+        });
+        match stream {
+            Ok(stream) => on_cleanup(move || drop(stream)),
+            Err(e) => leptos::logging::warn!("price stream unavailable: {}", e),
+        }
+    }
+
+    // --- HANDLER: VECTOR 0 (DEVICE KEY) ---
+    let unseal_key = move |_| {
+        if passphrase.get_untracked().is_empty() {
+            set_status_msg.set("ERROR: PASSPHRASE REQUIRED.".into());
+            return;
+        }
+        // A typo here would seal the new key under a passphrase nobody knows.
+        if !keys::has_stored_key() && passphrase_confirm.with_untracked(|c| *c != passphrase.get_untracked()) {
+            set_passphrase_confirm.set(String::new());
+            set_status_msg.set("ERROR: PASSPHRASES DO NOT MATCH.".into());
+            return;
+        }
+        if !dispatch(VaultEvent::UnsealStarted) { return; }
+        set_status_msg.set("DERIVING SEAL KEY...".into());
+        spawn_local(async move {
+            // Yield once so the status repaints before PBKDF2 blocks the thread.
+            TimeoutFuture::new(0).await;
+            let secret = zeroize::Zeroizing::new(passphrase.get_untracked());
+            set_passphrase.set(String::new());
+            set_passphrase_confirm.set(String::new());
+            match keys::load_or_create(&secret) {
+                Ok(key) => {
+                    device_key.set_value(Some(key));
+                    dispatch(VaultEvent::KeyUnsealed);
+                    set_status_msg.set("DEVICE KEY UNSEALED. WAITING FOR VECTOR 1.".into());
+                    // A wallet linked on an earlier visit reconnects without a prompt if it still trusts us.
+                    let remembered = wallet::remembered_wallet()
+                        .and_then(|name| wallet_registry.with_value(|r| r.borrow().get(&name).cloned()).map(|h| (name, h)));
+                    if let Some((name, handle)) = remembered {
+                        link_wallet(name, handle, true, linked_wallet, set_vault, set_status_msg);
+                    }
+                }
+                Err(keys::KeyError::WrongPassphrase) => {
+                    dispatch(VaultEvent::UnsealFailed);
+                    set_status_msg.set("ERROR: WRONG PASSPHRASE.".into());
+                }
+                Err(e) => {
+                    dispatch(VaultEvent::UnsealFailed);
+                    set_status_msg.set(format!("ERROR: {}.", e).to_uppercase());
+                }
+            }
+        });
+    };
+
+    // --- HANDLER: VECTOR 2 (IDENTITY) ---
+    let verify_bio = move |_| {
+        if !dispatch(VaultEvent::ScanStarted) { return; }
+        set_status_msg.set("SCANNING BIOMATRIX...".into());
+        spawn_local(async move {
+            let challenge = Uuid::new_v4();
+            let result = match biometric.get_value() {
+                Some(provider) => provider.verify_user(challenge.as_bytes()).await,
+                None => Err(BiometricError::Unsupported),
+            };
+            match result.and_then(|assertion| assertion.answers(challenge.as_bytes()).map(|()| assertion)) {
+                Ok(assertion) => {
+                    session_credential.set_value(Some(assertion.credential_id));
+                    dispatch(VaultEvent::IdentityVerified);
+                    set_status_msg.set("IDENTITY VERIFIED. ENGAGE HOLD TO REVEAL.".into());
+                }
+                Err(e) => {
+                    dispatch(VaultEvent::ScanFailed);
+                    set_status_msg.set(format!("ERROR: {}.", e).to_uppercase());
+                }
+            }
+        });
+    };
+
+    // --- HANDLER: STEALTH UNLOCK ---
+    let start_unlock = move |_| {
+        if !matches!(vault.get_untracked(), VaultState::Locked { .. }) { return false; }
+        set_status_msg.set("REVEALING VAULT DATA...".into());
+        true
+    };
+    let complete_unlock = move |_| {
+        dispatch(VaultEvent::Revealed);
+        set_status_msg.set("STEALTH MODE DEACTIVATED.".into());
+    };
+
+    // --- HANDLER: VECTOR 3 (INTENT ATTESTATION) ---
+    let start_pay = move |_| {
+        if !matches!(vault.get_untracked(), VaultState::Unlocked { .. }) { return false; }
+        if sealing.get_untracked() { return false; }
+        set_status_msg.set("ATTESTING HUMAN INTENT...".into());
+        true
+    };
+
+    // The attestation is sealed on release, with the measured press-to-release timing.
+    let finish_pay = move |timing: HoldTiming| {
+        let Some(signer_pubkey) = device_key.with_value(|key| key.as_ref().map(DeviceKey::public_key_hex)) else {
+            set_status_msg.set("ERROR: DEVICE KEY SEALED.".into());
+            return;
+        };
+        // Bind the price the user is looking at; refuse to seal against a stale or missing one.
+        let symbol = asset.get_untracked();
+        let now_ms = js_sys::Date::now() as u64;
+        let quote = match prices.with_untracked(|p| p.get(&symbol).cloned()) {
+            Some(Ok(price)) if !price.is_stale(now_ms, max_quote_age_ms) => price.bind(),
+            Some(Ok(_)) => {
+                set_status_msg.set("ERROR: PRICE STALE. WAIT FOR A FRESH QUOTE.".into());
+                return;
+            }
+            _ => {
+                set_status_msg.set("ERROR: NO PRICE QUOTE.".into());
+                return;
+            }
+        };
+        let quote = match quote {
+            Ok(quote) => quote,
+            Err(e) => {
+                set_status_msg.set(format!("ERROR: {}.", e).to_uppercase());
+                return;
+            }
+        };
+        // --- CANONICAL SIGNING ENGINE ---
+        let mut body = AttestationBody {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            context: SIGNING_CONTEXT.to_string(),
+            asset_symbol: symbol,
+            quote: Some(quote),
+            wallet_pubkey: vault.with_untracked(|v| v.wallet().unwrap_or_default().to_string()),
+            wallet_chain_id: linked_wallet.with_value(|w| w.as_ref().and_then(WalletHandle::chain_id)),
+            biometric_proof: String::new(), // Filled below by an assertion over this body
+            hold_duration_ms: timing.duration_ms(),
+            hold_started_ms: Some(timing.started_ms),
+            hold_ended_ms: Some(timing.ended_ms),
+            entropy_hash: format!("VEXT-HEX-{}", js_sys::Math::random()),
+            nonce: Uuid::new_v4().to_string(),
+            timestamp_utc: now_ms / 1000,
+            signer_pubkey,
+        };
+        let challenge = match body.biometric_challenge() {
+            Ok(challenge) => challenge,
+            Err(e) => {
+                set_status_msg.set(format!("ERROR: {}.", e).to_uppercase());
+                return;
+            }
+        };
+
+        set_sealing.set(true);
+        set_status_msg.set("CONFIRM IDENTITY TO SEAL...".into());
+        spawn_local(async move {
+            // The biometric assertion signs the body hash, binding identity to this exact intent.
+            let assertion = match biometric.get_value() {
+                Some(provider) => provider.verify_user(&challenge).await,
+                None => Err(BiometricError::Unsupported),
+            };
+            set_sealing.set(false);
+            let same_credential = |assertion: &BiometricAssertion| {
+                session_credential.with_value(|id| id.as_ref() == Some(&assertion.credential_id))
+            };
+            let assertion = assertion.and_then(|assertion| match assertion.answers(&challenge) {
+                Ok(()) if same_credential(&assertion) => Ok(assertion),
+                Ok(()) => Err(BiometricError::Failed("not the authenticator verified for this session".into())),
+                Err(e) => Err(e),
+            });
+            body.biometric_proof = match assertion {
+                Ok(assertion) => assertion.to_proof(),
+                Err(e) => {
+                    set_status_msg.set(format!("ERROR: {}.", e).to_uppercase());
+                    return;
+                }
+            };
+
+            // The wallet co-signs the same canonical bytes, proving control of wallet_pubkey.
+            set_status_msg.set("APPROVE CO-SIGNATURE IN WALLET...".into());
+            let wallet_signature = match linked_wallet.get_value() {
+                Some(wallet) => wallet.co_sign(&body).await,
+                None => Err(WalletError::Failed("not linked".into())),
+            };
+            let wallet_signature = match wallet_signature {
+                Ok(signature) => signature,
+                Err(e) => {
+                    set_status_msg.set(wallet_status(&e));
+                    return;
+                }
+            };
+
+            // The account may have switched while the wallet was prompting; never seal a stale key.
+            if vault.with_untracked(|v| v.wallet() != Some(body.wallet_pubkey.as_str())) {
+                set_status_msg.set("WALLET CHANGED. ATTESTATION DISCARDED.".into());
+                return;
+            }
+
+            // Seal the RFC 8785 canonical body with the device key
+            let new_auth = match device_key.with_value(|key| key.as_ref().map(|k| IntentAttestation::seal(body, k))) {
+                Some(Ok(sealed)) => sealed.with_wallet_signature(&wallet_signature),
+                Some(Err(e)) => {
+                    set_status_msg.set(format!("ERROR: {}.", e).to_uppercase());
+                    return;
+                }
+                None => {
+                    set_status_msg.set("ERROR: DEVICE KEY SEALED.".into());
+                    return;
+                }
+            };
+
+            set_receipt_leaf.set(None);
+            set_receipt.set(Some(new_auth.clone()));
+            dispatch(VaultEvent::IntentSealed);
+            match audit_store.get_value().append(&new_auth).await {
+                Ok(entry) => {
+                    // A chain already known to be broken stays broken.
+                    set_chain.update(|chain| {
+                        if let Some(Ok(head)) = chain {
+                            *chain = Some(head.link(&entry).map_err(AuditError::BrokenChain));
+                        }
+                    });
+                    // Short of the new entry, the startup rebuild has not caught
+                    // up yet; reading the log again fills the gap.
+                    if let Some(size) = sync_tree(std::slice::from_ref(&entry)) {
+                        if size < entry.seq {
+                            rebuild_tree();
+                        }
+                        set_receipt_leaf.set(Some(entry.seq - 1));
+                    }
+                    set_audit_log.update(|log| log.insert(0, entry));
+                    set_audit_total.update(|total| *total += 1);
+                    set_status_msg.set("ATTESTATION SIGNED & CANONICALIZED.".into());
+                }
+                Err(e) => set_status_msg.set(format!("ATTESTATION SIGNED. AUDIT LOG WRITE FAILED: {}.", e).to_uppercase()),
+            }
+        });
+    };
+
+
+    // --- VIEW ---
+    view! {
+        <div class="container">
+            <div class="vault-card">
+                <header>
+                    <div class="logo">"VEXT"</div>
+                    <button class="view-toggle" on:click=move |_| set_verifying.update(|v| *v = !*v)>
+                        {move || if verifying.get() { "VAULT" } else { "VERIFY" }}
+                    </button>
+                    <div class="status-pill" class:active=unlocked>
+                        {move || if unlocked() { "SECURE SESSION" } else { "VAULT SECURED" }}
+                    </div>
+                </header>
+
+                <Show when=move || verifying.get()>
+                    <VerifyView />
+                </Show>
+
+                <main class:blurred={move || !unlocked()} class:hidden=verifying>
+                    <div class="price-display">
+                        {assets.get_value().iter().map(|config| {
+                            let symbol = store_value(config.symbol.clone());
+                            let (name, currency) = (config.name.to_uppercase(), config.quote_currency.clone());
+                            let label = move || symbol.with_value(|s| price(s));
+                            view! {
+                                <div class="price-item"
+                                     class:selected={move || symbol.with_value(|s| asset.with(|a| a == s))}
+                                     class:stale={move || label().1.is_some()}
+                                     title={move || format!("{} · {}", name, symbol.with_value(|s| prices.with(|p| price_detail(p.get(s)))))}
+                                     on:click={move |_| set_asset.set(symbol.get_value())}>
+                                    <span>{symbol.get_value()}</span>
+                                    <span class="price-flag">{move || label().1}</span>
+                                    <strong>{move || with_currency(&label().0, &currency)}</strong>
+                                </div>
+                            }
+                        }).collect_view()}
+                    </div>
+
+                    <div class="history-log">
+                        <h3>
+                            {move || if audit_persistent.get() { "AUDIT LOG" } else { "SESSION AUDIT LOG" }}
+                            " · " {move || audit_total.get()}
+                            {move || {
+                                let (label, broken) = match chain.get() {
+                                    None => ("CHECKING CHAIN".to_string(), false),
+                                    Some(Ok(_)) => ("CHAIN INTACT".to_string(), false),
+                                    Some(Err(AuditError::BrokenChain(b))) => (format!("CHAIN BROKEN AT #{}", b.seq), true),
+                                    Some(Err(AuditError::Corrupt { seq, .. })) => (format!("ENTRY #{} CORRUPT", seq), true),
+                                    Some(Err(_)) => ("CHAIN UNCHECKED".to_string(), true),
+                                };
+                                let detail = chain.with(|c| match c {
+                                    Some(Ok(head)) => format!("HEAD {}", head.hash.get(0..16).unwrap_or_default()).to_uppercase(),
+                                    Some(Err(e)) => e.to_string().to_uppercase(),
+                                    None => String::new(),
+                                });
+                                view! { <span class="chain-badge" class:broken=broken title=detail>{label}</span> }
+                            }}
+                        </h3>
+                        {move || checkpoints.with(|c| c.last().map(|signed| {
+                            let cp = &signed.checkpoint;
+                            let root = cp.root_hash.get(0..16).unwrap_or_default().to_uppercase();
+                            view! { <div class="checkpoint-line">"CHECKPOINT " {cp.tree_size} " · ROOT " {root}</div> }
+                        }))}
+                        <div class="log-entries">
+                            <For each=move || audit_log.get() key=|entry| entry.seq let:entry>
+                                <div class="log-entry">
+                                    <span>"#" {entry.seq}</span>
+                                    <span>{entry.attestation.body.asset_symbol.clone()}</span>
+                                    <span class="log-hash">{entry.attestation.signature.get(0..8).unwrap_or_default().to_string()}</span>
+                                    <span>"✓"</span>
+                                </div>
+                            </For>
+                            <Show when=move || audit_next.get().is_some()>
+                                <button class="log-more" on:click=move |_| {
+                                    // Clear the cursor first so a double click cannot fetch the same page twice.
+                                    if let Some(before) = audit_next.get_untracked() {
+                                        set_audit_next.set(None);
+                                        load_audit_page(Some(before));
+                                    }
+                                }>"LOAD OLDER"</button>
+                            </Show>
+                        </div>
+                        <Show when=move || audit_total.get() != 0>
+                            <div class="log-export">
+                                <button on:click=move |_| export_log(ExportFormat::JsonLines)>"JSONL"</button>
+                                <button on:click=move |_| export_log(ExportFormat::Csv)>"CSV"</button>
+                                <button
+                                    on:click=move |_| export_log(ExportFormat::Bundle)
+                                    disabled=move || !vault.with(VaultState::key_ready)
+                                >"SIGNED BUNDLE"</button>
+                            </div>
+                        </Show>
+                    </div>
+                </main>
+
+                <div class="status-monitor" class:hidden=verifying style="font-size: 10px; color: #3b82f6; text-align: center; margin: 15px 0; font-family: monospace; letter-spacing: 0.05em; text-transform: uppercase;">
+                    {move || status_msg.get()}
+                </div>
+
+                <footer class="controls" class:hidden=verifying>
+                    <div class="step-indicator">
+                        <div class="step" class:done={move || vault.with(VaultState::wallet_linked)}>"1"</div>
+                        <div class="step" class:done={move || vault.with(VaultState::identity_verified)}>"2"</div>
+                        <div class="step" class:done=unlocked>"3"</div>
+                    </div>
+
+                    <Show when=move || vault.with(VaultState::wallet_linked)>
+                        <button class="unlink-btn" on:click=unlink_wallet>"UNLINK WALLET"</button>
+                    </Show>
+
+                    <div class="button-stack">
+                        {move || match vault.get() {
+                        VaultState::KeySealed { unsealing } => {
+                            view! {
+                                <input class="passphrase-input" type="password"
+                                    placeholder="DEVICE PASSPHRASE"
+                                    prop:value={move || passphrase.get()}
+                                    on:input={move |ev| {
+                                        let input = event_target::<HtmlInputElement>(&ev);
+                                        set_passphrase.set(input.value());
+                                    }}
+                                />
+                                <Show when=move || !keys::has_stored_key()>
+                                    <input class="passphrase-input" type="password"
+                                        placeholder="CONFIRM PASSPHRASE"
+                                        prop:value={move || passphrase_confirm.get()}
+                                        on:input={move |ev| {
+                                            let input = event_target::<HtmlInputElement>(&ev);
+                                            set_passphrase_confirm.set(input.value());
+                                        }}
+                                    />
+                                </Show>
+                                <button class="action-btn primary" on:click={unseal_key} disabled=unsealing>
+                                    {if unsealing { "UNSEALING..." } else { "UNSEAL DEVICE KEY" }}
+                                </button>
+                            }.into_view()
+                        }
+                        VaultState::AwaitingWallet => {
+                            view! {
+                                <div class="wallet-picker">
+                                    <For
+                                        each=move || wallets.get()
+                                        key=|w| w.name.clone()
+                                        children=move |w: WalletInfo| {
+                                            let name = w.name.clone();
+                                            view! {
+                                                <button class="action-btn primary wallet-option" on:click={move |_| {
+                                                    let handle = wallet_registry.with_value(|r| r.borrow().get(&name).cloned());
+                                                    match handle {
+                                                        Some(handle) => link_wallet(name.clone(), handle, false, linked_wallet, set_vault, set_status_msg),
+                                                        None => set_status_msg.set(wallet_status(&WalletError::NotInstalled)),
+                                                    }
+                                                }}>
+                                                    {w.icon.map(|src| view! { <img class="wallet-icon" src=src alt="" /> })}
+                                                    {format!("LINK {}", w.name.to_uppercase())}
+                                                </button>
+                                            }
+                                        }
+                                    />
+                                    <Show when=move || wallets.with(Vec::is_empty)>
+                                        <button class="action-btn primary" disabled=true>"NO WALLET DETECTED"</button>
+                                    </Show>
+                                </div>
+                            }.into_view()
+                        }
+                        VaultState::AwaitingBiometric { scanning, .. } => {
+                            view! {
+                                <button class="action-btn primary" on:click={verify_bio} disabled=scanning>
+                                    {if scanning { "SCANNING..." } else { "SCAN BIOMATRIX" }}
+                                </button>
+                            }.into_view()
+                        }
+                        VaultState::Locked { .. } => {
+                            view! {
+                                <HoldGesture
+                                    duration_ms=1000
+                                    on_start=start_unlock
+                                    on_complete=complete_unlock
+                                    on_cancel=move |_| set_status_msg.set("HOLD INTERRUPTED.".into())
+                                >
+                                    "HOLD TO REVEAL"
+                                </HoldGesture>
+                            }.into_view()
+                        }
+                        VaultState::Unlocked { .. } | VaultState::Receipt { .. } => {
+                            view! {
+                                <HoldGesture
+                                    duration_ms=min_hold_ms
+                                    complete_on_release=true
+                                    disabled=Signal::derive(move || matches!(vault.get(), VaultState::Receipt { .. }))
+                                    class="action-btn authorize"
+                                    bar_class="progress-bar auth"
+                                    on_start=start_pay
+                                    on_armed=move |_| set_status_msg.set("INTENT CAPTURED. RELEASE TO SEAL.".into())
+                                    on_complete=finish_pay
+                                    on_cancel=move |e: HoldError| set_status_msg.set(format!("AUTHORIZATION FAILED: {}.", e).to_uppercase())
+                                >
+                                    {move || if matches!(vault.get(), VaultState::Receipt { .. }) { "VERIFIED" } else { "HOLD TO AUTHORIZE" }}
+                                </HoldGesture>
+                            }.into_view()
+                        }
+                        }}
+                    </div>
+                </footer>
+
+                {move || {
+                    if let Some(last) = receipt.get() {
+                        if matches!(vault.get(), VaultState::Receipt { .. }) {
+                            let sig_display = format!("{}...", last.signature.get(0..16).unwrap_or(""));
+                            let hold_display = format!("{} MS", last.body.hold_duration_ms);
+                            let quote_display = match &last.body.quote {
+                                Some(q) => format!("{} {}", q.price, q.currency),
+                                None => "NONE".to_string(),
+                            };
+                            let leaf_display = receipt_leaf.get().map_or("UNLOGGED".to_string(), |leaf| format!("#{}", leaf));
+                            let nonce_display = last.body.nonce.get(0..8).unwrap_or("").to_string();
+                            let signer_display = format!("{}...", last.body.signer_pubkey.get(0..16).unwrap_or(""));
+                            let wallet_display = match &last.wallet_signature {
+                                Some(sig) => format!("{}...", sig.get(0..16).unwrap_or("")),
+                                None => "UNSIGNED".to_string(),
+                            };
+                            let mut policy = Policy::new((js_sys::Date::now() / 1000.0) as u64);
+                            policy.min_hold_ms = Some(min_hold_ms);
+                            let sealed_ok = device_key.with_value(|key| {
+                                key.as_ref().is_some_and(|k| verify::verify_attestation(&last, &k.verifying_key(), &policy).is_ok())
+                            });
+                            let seal_tag = if sealed_ok { "CANONICAL VEXT SEAL · VERIFIED" } else { "VEXT SEAL INVALID" };
+                            
+                            return view! {
+                                <div class="receipt-overlay">
+                                    <div class="jagged-receipt">
+                                        <h3>"INTENT SIGNED"</h3>
+                                        <div class="receipt-row"><span>"ASSET"</span><span>{last.body.asset_symbol}</span></div>
+                                        <div class="receipt-row"><span>"QUOTE"</span><span>{quote_display}</span></div>
+                                        <div class="receipt-row"><span>"LEAF"</span><span>{leaf_display}</span></div>
+                                        <div class="receipt-row"><span>"SIG"</span><span style="font-size:8px">{sig_display}</span></div>
+                                        <div class="receipt-row"><span>"HOLD"</span><span>{hold_display}</span></div>
+                                        <div class="receipt-row"><span>"NONCE"</span><span style="font-size:8px">{nonce_display}</span></div>
+                                        <div class="receipt-row"><span>"SIGNER"</span><span style="font-size:8px">{signer_display}</span></div>
+                                        <div class="receipt-row"><span>"WALLET SIG"</span><span style="font-size:8px">{wallet_display}</span></div>
+                                        <div class="receipt-tag">{seal_tag}</div>
+                                        <button class="dismiss-btn" on:click={move |_| { dispatch(VaultEvent::ReceiptDismissed); }}>"DONE"</button>
+                                    </div>
+                                </div>
+                            }.into_view();
+                        }
+                    }
+                    view! { <div class="hidden"></div> }.into_view()
+                }}
+            </div>
+        </div>
+    }
+}
+
+// --- HELPER: WALLET LOGIC ---
+/// Connects `wallet` (silently when reconnecting a remembered one) and follows its account changes.
+fn link_wallet(
+    name: String,
+    wallet: WalletHandle,
+    silent: bool,
+    linked_wallet: StoredValue<Option<WalletHandle>>,
+    set_vault: WriteSignal<VaultState>,
+    set_status: WriteSignal<String>,
+) {
+    spawn_local(async move {
+        set_status.set(if silent { format!("RECONNECTING {}...", name.to_uppercase()) } else { "HANDSHAKING...".into() });
+        let key = match if silent { wallet.reconnect().await } else { wallet.connect().await } {
+            Ok(key) => key,
+            // No longer trusted: fall back to the picker without alarming the user.
+            Err(_) if silent => {
+                wallet::remember_wallet(None);
+                set_status.set("DEVICE KEY UNSEALED. WAITING FOR VECTOR 1.".into());
+                return;
+            }
+            Err(e) => {
+                set_status.set(wallet_status(&e));
+                return;
+            }
+        };
+        let mut linked = false;
+        set_vault.update(|state| linked = state.apply(VaultEvent::WalletLinked(key)).is_ok());
+        if !linked {
+            return;
+        }
+        linked_wallet.set_value(Some(wallet.clone()));
+        wallet::remember_wallet(Some(&name));
+        set_status.set("VECTOR 1 SECURED. SCAN BIOMATRIX.".into());
+
+        wallet.watch_account(move |account| match account {
+            Some(key) => {
+                let mut changed = false;
+                set_vault.update(|state| changed = state.apply(VaultEvent::WalletChanged(key)).is_ok());
+                if changed {
+                    set_status.set("WALLET ACCOUNT CHANGED. RE-VERIFY IDENTITY.".into());
+                }
+            }
+            None => {
+                linked_wallet.set_value(None);
+                wallet::remember_wallet(None);
+                set_vault.update(|state| {
+                    let _ = state.apply(VaultEvent::WalletUnlinked);
+                });
+                set_status.set("WALLET DISCONNECTED. LINK A WALLET.".into());
+            }
+        });
+    });
+}
+
+fn wallet_status(error: &WalletError) -> String {
+    match error {
+        WalletError::NotInstalled => "ERROR: WALLET NOT INSTALLED.".into(),
+        WalletError::UserRejected => "WALLET REQUEST REJECTED. RETRY TO LINK.".into(),
+        WalletError::Locked => "WALLET LOCKED. UNLOCK IT AND RETRY.".into(),
+        WalletError::Timeout => "WALLET DID NOT RESPOND. RETRY.".into(),
+        WalletError::MalformedResponse(_) | WalletError::Failed(_) => format!("ERROR: {}.", error).to_uppercase(),
+    }
+}
@@ -0,0 +1,191 @@
+/* ===================== ASSET REGISTRY ===================== */
+
+// The assets the vault can price and attest, as data rather than code. A desk
+// ships its own list as JSON; without one the vault offers BTC, ETH and SOL
+// against the US dollar.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::oracle::{Pair, DEFAULT_QUOTE_CURRENCY};
+
+/// Highest token precision accepted; ERC-20 tokens top out at 18 in practice.
+pub const MAX_DECIMALS: u8 = 36;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssetError {
+    /// The configuration is not valid JSON for a list of assets.
+    Parse(String),
+    Empty,
+    DuplicateSymbol(String),
+    Invalid { symbol: String, reason: String },
+}
+
+impl std::fmt::Display for AssetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetError::Parse(e) => write!(f, "asset config unreadable: {}", e),
+            AssetError::Empty => write!(f, "asset config lists no assets"),
+            AssetError::DuplicateSymbol(symbol) => write!(f, "asset {} listed twice", symbol),
+            AssetError::Invalid { symbol, reason } => write!(f, "asset {}: {}", symbol, reason),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+fn default_quote_currency() -> String {
+    DEFAULT_QUOTE_CURRENCY.into()
+}
+
+/// One priceable asset.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AssetConfig {
+    /// Ticker, also the `asset_symbol` of attestations, e.g. "BTC".
+    pub symbol: String,
+    /// Display name, e.g. "Bitcoin".
+    pub name: String,
+    /// Token precision on its chain (8 for BTC, 18 for ETH, 9 for SOL).
+    pub decimals: u8,
+    /// The chain the asset settles on, e.g. "bitcoin", "ethereum", "solana".
+    pub chain: String,
+    #[serde(default = "default_quote_currency")]
+    pub quote_currency: String,
+    /// Oracle name to market id, for exchanges whose ids the default naming
+    /// rules get wrong, e.g. `{"kraken": "XXBTZUSD"}`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub oracle_ids: BTreeMap<String, String>,
+}
+
+impl AssetConfig {
+    pub fn new(symbol: &str, name: &str, decimals: u8, chain: &str) -> Self {
+        Self {
+            symbol: symbol.into(),
+            name: name.into(),
+            decimals,
+            chain: chain.into(),
+            quote_currency: default_quote_currency(),
+            oracle_ids: BTreeMap::new(),
+        }
+    }
+
+    /// What the oracles are asked to price for this asset.
+    pub fn pair(&self) -> Pair {
+        Pair { symbol: self.symbol.clone(), currency: self.quote_currency.clone(), markets: self.oracle_ids.clone() }
+    }
+
+    fn validate(&self) -> Result<(), AssetError> {
+        let invalid = |reason: &str| AssetError::Invalid { symbol: self.symbol.clone(), reason: reason.into() };
+        // Symbols and currencies end up in exchange URLs and signed bodies.
+        let is_code = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+        if !is_code(&self.symbol) {
+            return Err(invalid("symbol must be uppercase letters and digits"));
+        }
+        if !is_code(&self.quote_currency) {
+            return Err(invalid("quote currency must be uppercase letters and digits"));
+        }
+        if self.name.trim().is_empty() {
+            return Err(invalid("name is empty"));
+        }
+        if self.chain.trim().is_empty() {
+            return Err(invalid("chain is empty"));
+        }
+        if self.decimals > MAX_DECIMALS {
+            return Err(invalid("too many decimals"));
+        }
+        if self.oracle_ids.values().any(|id| id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')) {
+            return Err(invalid("oracle ids must be letters, digits and dashes"));
+        }
+        Ok(())
+    }
+}
+
+/// The assets on offer, in display order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssetRegistry {
+    assets: Vec<AssetConfig>,
+}
+
+impl Default for AssetRegistry {
+    fn default() -> Self {
+        Self {
+            assets: vec![
+                AssetConfig::new("BTC", "Bitcoin", 8, "bitcoin"),
+                AssetConfig::new("ETH", "Ether", 18, "ethereum"),
+                AssetConfig::new("SOL", "Solana", 9, "solana"),
+            ],
+        }
+    }
+}
+
+impl AssetRegistry {
+    /// Validates `assets`: at least one, unique symbols, well-formed fields.
+    pub fn new(assets: Vec<AssetConfig>) -> Result<Self, AssetError> {
+        if assets.is_empty() {
+            return Err(AssetError::Empty);
+        }
+        for (i, asset) in assets.iter().enumerate() {
+            asset.validate()?;
+            if assets[..i].iter().any(|a| a.symbol == asset.symbol) {
+                return Err(AssetError::DuplicateSymbol(asset.symbol.clone()));
+            }
+        }
+        Ok(Self { assets })
+    }
+
+    /// Reads a JSON array of `AssetConfig`.
+    pub fn from_json(config: &str) -> Result<Self, AssetError> {
+        let assets = serde_json::from_str(config).map_err(|e| AssetError::Parse(e.to_string()))?;
+        Self::new(assets)
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&AssetConfig> {
+        self.assets.iter().find(|a| a.symbol == symbol)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, AssetConfig> {
+        self.assets.iter()
+    }
+
+    /// Never empty, so there is always a first asset to preselect.
+    pub fn first(&self) -> &AssetConfig {
+        &self.assets[0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a AssetRegistry {
+    type Item = &'a AssetConfig;
+    type IntoIter = std::slice::Iter<'a, AssetConfig>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.assets.iter()
+    }
+}
+
+/* ===================== BROWSER CONFIG ===================== */
+
+#[cfg(target_arch = "wasm32")]
+pub use browser::{from_document, ASSET_CONFIG_ELEMENT_ID};
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use super::{AssetError, AssetRegistry};
+
+    /// `<script type="application/json" id="vext-assets">[...]</script>` in the host page.
+    pub const ASSET_CONFIG_ELEMENT_ID: &str = "vext-assets";
+
+    /// The registry configured in the host page, or `None` if it configures none.
+    pub fn from_document() -> Option<Result<AssetRegistry, AssetError>> {
+        let element = web_sys::window()?.document()?.get_element_by_id(ASSET_CONFIG_ELEMENT_ID)?;
+        Some(AssetRegistry::from_json(&element.text_content().unwrap_or_default()))
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::jcs::{self, JcsError};
+use crate::keys::DeviceKey;
+use crate::schema::{self, VersionedAttestation};
+use crate::wallet::WalletKind;
+
+/* ===================== HARDENED ATTESTATION DATA ===================== */
+
+/// Domain separator carried in every body so a VEXT seal cannot be replayed
+/// as a signature over some other JSON document.
+pub const SIGNING_CONTEXT: &str = "vext.intent-attestation";
+
+/// Schema written by this build. See `schema` for older layouts.
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// The unsigned statement of human intent. Every field here is covered by the
+/// device signature; this is the single source of truth for the signed schema.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AttestationBody {
+    pub schema_version: u32,     // Signed, so a verifier can trust the layout
+    pub context: String,         // Always SIGNING_CONTEXT
+    pub asset_symbol: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<PriceQuote>, // The price on screen when intent was captured
+    pub wallet_pubkey: String,   // Base58 Solana key or 0x Ethereum address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet_chain_id: Option<u64>, // EIP-155 chain of an Ethereum wallet
+    pub biometric_proof: String, // BiometricAssertion::to_proof over biometric_challenge()
+    pub hold_duration_ms: u64,    // Measured: hold_ended_ms - hold_started_ms
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hold_started_ms: Option<u64>, // Unix epoch ms of the press
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hold_ended_ms: Option<u64>,   // Unix epoch ms of the release
+    pub entropy_hash: String,     
+    pub nonce: String,           // Unique ID to prevent Replay Attacks
+    pub timestamp_utc: u64,      // Unix Epoch for TTL validation
+    pub signer_pubkey: String,   // Hex Ed25519 key of the sealing device
+}
+
+/// A price as the user saw it, bound into the signed body so a verifier knows
+/// what was on screen. Built by `oracle::AggregatedPrice::bind`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PriceQuote {
+    pub source: String,        // How the price was formed, e.g. "median:coinbase,kraken"
+    pub price: String,         // Decimal string exactly as displayed
+    pub currency: String,      // Quote currency, e.g. "USD"
+    pub quoted_at_ms: u64,     // Unix epoch ms the price was current
+    pub quote_id: String,      // Hex SHA-256 of the JCS component quotes
+}
+
+impl AttestationBody {
+    /// Exact bytes covered by the device signature: the RFC 8785 canonical body,
+    /// or for records upgraded from schema 1 the original legacy payload.
+    pub fn signing_message(&self) -> Result<Vec<u8>, JcsError> {
+        match self.schema_version {
+            1 => schema::v1_signing_message(self),
+            _ => jcs::to_canonical_vec(self),
+        }
+    }
+
+    /// WebAuthn challenge binding a biometric assertion to this exact body:
+    /// SHA-256 of the canonical body with `biometric_proof` left empty.
+    /// `signer_pubkey` must already be set, since it is part of the hash.
+    pub fn biometric_challenge(&self) -> Result<[u8; 32], JcsError> {
+        let unproven = AttestationBody { biometric_proof: String::new(), ..self.clone() };
+        Ok(Sha256::digest(jcs::to_canonical_vec(&unproven)?).into())
+    }
+}
+
+/// The data object representing a verified human intent.
+/// This matches the schema expected by the Institutional Verifier.
+/// Deserializing accepts every stored layout and upgrades it to this shape.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(from = "VersionedAttestation")]
+pub struct IntentAttestation {
+    pub body: AttestationBody,
+    pub signature: String,       // Ed25519 Cryptographic Seal over body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet_signature: Option<String>, // Base58 wallet signMessage over the same bytes
+}
+
+impl IntentAttestation {
+    /// Stamps the device public key into `body` and seals its canonical form.
+    pub fn seal(mut body: AttestationBody, key: &DeviceKey) -> Result<Self, JcsError> {
+        body.signer_pubkey = key.public_key_hex();
+        let signature = key.sign(&body.signing_message()?);
+        Ok(Self { body, signature: hex::encode(signature.to_bytes()), wallet_signature: None })
+    }
+
+    /// Attaches the wallet co-signature: `signMessage` over `body.signing_message()`
+    /// for Solana, `eth_signTypedData_v4` over `eip712::typed_data` for Ethereum.
+    pub fn with_wallet_signature(mut self, signature: &[u8]) -> Self {
+        self.wallet_signature = Some(WalletKind::of(&self.body.wallet_pubkey).encode_signature(signature));
+        self
+    }
+}
@@ -0,0 +1,476 @@
+/* ===================== AUDIT LOG STORAGE ===================== */
+
+// Every sealed attestation is appended to an audit store and read back in
+// pages, newest first. Entries are kept as JSON, so attestations written under
+// an older schema are upgraded on read like any other import. The browser
+// keeps them in IndexedDB; `MemoryAuditStore` backs tests and browsers without it.
+//
+// The log is hash-chained: each entry records the SHA-256 of the one before it,
+// and an entry's own hash covers its sequence number, that link and the
+// attestation. Deleting, reordering or editing any entry but the newest breaks
+// the next link, which `verify_chain` reports.
+
+use std::cell::RefCell;
+
+use futures::future::{ready, LocalBoxFuture};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::attestation::IntentAttestation;
+use crate::jcs;
+
+/// Entries fetched per page by the log view.
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+
+/// `prev_hash` of the first entry.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuditError {
+    /// No storage backend in this environment (e.g. private browsing without IndexedDB).
+    Unavailable,
+    Storage(String),
+    /// A stored entry no longer parses as an attestation.
+    Corrupt { seq: u64, reason: String },
+    Encode(String),
+    BrokenChain(ChainBreak),
+}
+
+impl std::fmt::Display for AuditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuditError::Unavailable => write!(f, "audit storage unavailable"),
+            AuditError::Storage(e) => write!(f, "audit storage failed: {}", e),
+            AuditError::Corrupt { seq, reason } => write!(f, "audit entry {} corrupt: {}", seq, reason),
+            AuditError::Encode(e) => write!(f, "cannot encode attestation: {}", e),
+            AuditError::BrokenChain(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+impl std::error::Error for AuditError {}
+
+/// One stored attestation and its position in the log. Sequence numbers start
+/// at 1 and only grow.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AuditEntry {
+    pub seq: u64,
+    /// Hex SHA-256 of the previous entry, or `GENESIS_HASH` for the first.
+    pub prev_hash: String,
+    pub attestation: IntentAttestation,
+}
+
+impl AuditEntry {
+    /// Hex SHA-256 over the canonical `{attestation, prev_hash, seq}`.
+    pub fn hash(&self) -> Result<String, AuditError> {
+        let canonical = jcs::to_canonical_vec(&json!({
+            "attestation": self.attestation,
+            "prev_hash": self.prev_hash,
+            "seq": self.seq,
+        }))
+        .map_err(|e| AuditError::Encode(e.to_string()))?;
+        Ok(hex::encode(Sha256::digest(canonical)))
+    }
+}
+
+/// The first point where the chain stops holding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainBreak {
+    pub seq: u64,
+    pub kind: BreakKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BreakKind {
+    /// Entries are missing or out of order: `expected` should have come next.
+    Gap { expected: u64 },
+    /// `prev_hash` is not the hash of the entry before; that entry was altered
+    /// or removed, or this one was moved.
+    Mismatch { expected: String, found: String },
+    /// The entry cannot be hashed at all.
+    Unhashable(String),
+}
+
+impl std::fmt::Display for ChainBreak {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            BreakKind::Gap { expected } => write!(f, "audit chain broken at {}: expected entry {}", self.seq, expected),
+            BreakKind::Mismatch { .. } => write!(f, "audit chain broken at {}: previous hash mismatch", self.seq),
+            BreakKind::Unhashable(e) => write!(f, "audit chain broken at {}: {}", self.seq, e),
+        }
+    }
+}
+
+/// The end of a verified chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainHead {
+    /// Number of entries checked, which is also the newest sequence number.
+    pub len: u64,
+    /// Hash of the newest entry; `GENESIS_HASH` for an empty log.
+    pub hash: String,
+}
+
+impl ChainHead {
+    pub fn genesis() -> Self {
+        Self { len: 0, hash: GENESIS_HASH.into() }
+    }
+
+    /// Extends the head by `entry`, or reports where it fails to link.
+    pub fn link(&self, entry: &AuditEntry) -> Result<ChainHead, ChainBreak> {
+        let brk = |kind| ChainBreak { seq: entry.seq, kind };
+        if entry.seq != self.len + 1 {
+            return Err(brk(BreakKind::Gap { expected: self.len + 1 }));
+        }
+        if entry.prev_hash != self.hash {
+            return Err(brk(BreakKind::Mismatch { expected: self.hash.clone(), found: entry.prev_hash.clone() }));
+        }
+        let hash = entry.hash().map_err(|e| brk(BreakKind::Unhashable(e.to_string())))?;
+        Ok(ChainHead { len: entry.seq, hash })
+    }
+}
+
+/// Walks `entries` oldest first from sequence number 1 and returns the head,
+/// or the first broken link.
+pub fn verify_chain<'a>(entries: impl IntoIterator<Item = &'a AuditEntry>) -> Result<ChainHead, ChainBreak> {
+    entries.into_iter().try_fold(ChainHead::genesis(), |head, entry| head.link(entry))
+}
+
+/// The whole log from `store`, oldest first.
+pub async fn read_all(store: &dyn AuditStore) -> Result<Vec<AuditEntry>, AuditError> {
+    let mut entries = Vec::new();
+    let mut before = None;
+    loop {
+        let page = store.page(before, DEFAULT_PAGE_SIZE * 10).await?;
+        entries.extend(page.entries);
+        match page.next {
+            Some(next) => before = Some(next),
+            None => break,
+        }
+    }
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Reads the whole log from `store` and verifies its chain.
+pub async fn verify_store(store: &dyn AuditStore) -> Result<ChainHead, AuditError> {
+    verify_chain(&read_all(store).await?).map_err(AuditError::BrokenChain)
+}
+
+/// A slice of the log, newest first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Page {
+    pub entries: Vec<AuditEntry>,
+    /// Pass as `before` to fetch the next, older page; `None` once the oldest entry is in.
+    pub next: Option<u64>,
+}
+
+/// Append-only, hash-chained attestation storage.
+pub trait AuditStore {
+    /// Persists `attestation` linked to the current newest entry and returns the new entry.
+    fn append<'a>(&'a self, attestation: &'a IntentAttestation) -> LocalBoxFuture<'a, Result<AuditEntry, AuditError>>;
+
+    /// Up to `limit` entries older than `before` (or the newest, if `None`), newest first.
+    fn page(&self, before: Option<u64>, limit: usize) -> LocalBoxFuture<'_, Result<Page, AuditError>>;
+
+    fn count(&self) -> LocalBoxFuture<'_, Result<u64, AuditError>>;
+}
+
+/// What is stored per entry; the sequence number is the storage key.
+#[derive(Serialize, Deserialize)]
+struct Record {
+    prev_hash: String,
+    attestation: IntentAttestation,
+}
+
+pub(crate) fn encode(entry: &AuditEntry) -> Result<String, AuditError> {
+    let record = Record { prev_hash: entry.prev_hash.clone(), attestation: entry.attestation.clone() };
+    serde_json::to_string(&record).map_err(|e| AuditError::Encode(e.to_string()))
+}
+
+pub(crate) fn decode(seq: u64, json: &str) -> Result<AuditEntry, AuditError> {
+    serde_json::from_str::<Record>(json)
+        .map(|r| AuditEntry { seq, prev_hash: r.prev_hash, attestation: r.attestation })
+        .map_err(|e| AuditError::Corrupt { seq, reason: e.to_string() })
+}
+
+/// Chains a log written before entries were hash-linked. `stored` holds the
+/// raw values in storage order, keyed by sequence number: bare attestations
+/// (the original layout) are linked to the entry before them, values already
+/// in the chained layout are kept as they are.
+pub fn upgrade_legacy(stored: Vec<(u64, String)>) -> Result<Vec<AuditEntry>, AuditError> {
+    let mut entries: Vec<AuditEntry> = Vec::with_capacity(stored.len());
+    for (seq, json) in stored {
+        let entry = match decode(seq, &json) {
+            Ok(entry) => entry,
+            Err(chained) => {
+                let attestation = serde_json::from_str(&json).map_err(|_| chained)?;
+                let prev_hash = match entries.last() {
+                    Some(prev) => prev.hash()?,
+                    None => GENESIS_HASH.to_string(),
+                };
+                AuditEntry { seq, prev_hash, attestation }
+            }
+        };
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// The entry that would follow `newest` in the chain.
+pub(crate) fn next_entry(newest: Option<&AuditEntry>, attestation: &IntentAttestation) -> Result<AuditEntry, AuditError> {
+    let (seq, prev_hash) = match newest {
+        Some(newest) => (newest.seq + 1, newest.hash()?),
+        None => (1, GENESIS_HASH.to_string()),
+    };
+    Ok(AuditEntry { seq, prev_hash, attestation: attestation.clone() })
+}
+
+/// Builds a page from up to `limit + 1` entries read newest first: the extra
+/// one only tells whether an older page exists.
+fn paginate(mut entries: Vec<AuditEntry>, limit: usize) -> Page {
+    let more = entries.len() > limit;
+    entries.truncate(limit);
+    let next = if more { entries.last().map(|e| e.seq) } else { None };
+    Page { entries, next }
+}
+
+/// Keeps the log for the life of the process.
+#[derive(Default)]
+pub struct MemoryAuditStore {
+    /// Encoded entries; index `i` holds sequence number `i + 1`.
+    entries: RefCell<Vec<String>>,
+}
+
+impl MemoryAuditStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl AuditStore for MemoryAuditStore {
+    fn append<'a>(&'a self, attestation: &'a IntentAttestation) -> LocalBoxFuture<'a, Result<AuditEntry, AuditError>> {
+        let mut entries = self.entries.borrow_mut();
+        let result = entries
+            .last()
+            .map(|json| decode(entries.len() as u64, json))
+            .transpose()
+            .and_then(|newest| next_entry(newest.as_ref(), attestation))
+            .and_then(|entry| {
+                entries.push(encode(&entry)?);
+                Ok(entry)
+            });
+        Box::pin(ready(result))
+    }
+
+    fn page(&self, before: Option<u64>, limit: usize) -> LocalBoxFuture<'_, Result<Page, AuditError>> {
+        let entries = self.entries.borrow();
+        let end = before.map_or(entries.len(), |b| (b.saturating_sub(1) as usize).min(entries.len()));
+        let result = entries[..end]
+            .iter()
+            .enumerate()
+            .rev()
+            .take(limit + 1)
+            .map(|(i, json)| decode(i as u64 + 1, json))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|read| paginate(read, limit));
+        Box::pin(ready(result))
+    }
+
+    fn count(&self) -> LocalBoxFuture<'_, Result<u64, AuditError>> {
+        Box::pin(ready(Ok(self.entries.borrow().len() as u64)))
+    }
+}
+
+/* ===================== INDEXEDDB ===================== */
+
+#[cfg(target_arch = "wasm32")]
+pub use browser::{IndexedDbAuditStore, AUDIT_DB_NAME};
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use std::cell::RefCell;
+
+    use futures::future::LocalBoxFuture;
+    use js_sys::Promise;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{
+        IdbCursorDirection, IdbCursorWithValue, IdbDatabase, IdbKeyRange, IdbObjectStore, IdbOpenDbRequest, IdbRequest,
+        IdbTransactionMode, IdbVersionChangeEvent,
+    };
+
+    use super::{decode, encode, next_entry, paginate, upgrade_legacy, AuditEntry, AuditError, AuditStore, Page};
+    use crate::attestation::IntentAttestation;
+
+    pub const AUDIT_DB_NAME: &str = "vext-audit";
+    /// 1: bare attestations under auto-increment keys. 2: hash-chained records
+    /// keyed by sequence number.
+    const DB_VERSION: u32 = 2;
+    const STORE: &str = "attestations";
+
+    fn storage_error(e: JsValue) -> AuditError {
+        let message = e
+            .dyn_ref::<js_sys::Error>()
+            .map(|e| String::from(e.message()))
+            .or_else(|| e.as_string())
+            .unwrap_or_else(|| "unknown error".into());
+        AuditError::Storage(message)
+    }
+
+    /// Resolves with the request's next `success` result. Cursor requests fire
+    /// once per step, so this is awaited again after each `continue_`.
+    async fn settle(request: &IdbRequest) -> Result<JsValue, AuditError> {
+        let promise = Promise::new(&mut |resolve, reject| {
+            let on_success = {
+                let request = request.clone();
+                Closure::once_into_js(move || {
+                    let _ = resolve.call1(&JsValue::NULL, &request.result().unwrap_or(JsValue::UNDEFINED));
+                })
+            };
+            let on_error = {
+                let request = request.clone();
+                Closure::once_into_js(move || {
+                    let error = request.error().ok().flatten().map(JsValue::from).unwrap_or(JsValue::NULL);
+                    let _ = reject.call1(&JsValue::NULL, &error);
+                })
+            };
+            request.set_onsuccess(Some(on_success.unchecked_ref()));
+            request.set_onerror(Some(on_error.unchecked_ref()));
+        });
+        JsFuture::from(promise).await.map_err(storage_error)
+    }
+
+    async fn open(name: &str) -> Result<IdbDatabase, AuditError> {
+        let factory = web_sys::window()
+            .and_then(|w| w.indexed_db().ok().flatten())
+            .ok_or(AuditError::Unavailable)?;
+        let request: IdbOpenDbRequest = factory.open_with_u32(name, DB_VERSION).map_err(storage_error)?;
+        let on_upgrade = {
+            let request = request.clone();
+            Closure::once_into_js(move |event: IdbVersionChangeEvent| {
+                let Ok(db) = request.result().and_then(|r| r.dyn_into::<IdbDatabase>()) else { return };
+                let Some(transaction) = request.transaction() else { return };
+                if event.old_version() < 1.0 {
+                    let _ = db.create_object_store(STORE);
+                    return;
+                }
+                let Ok(store) = transaction.object_store(STORE) else { return };
+                // The migration runs inside the upgrade transaction: the new
+                // version only sticks if every entry was rewritten.
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Err(e) = chain_v1_entries(&store).await {
+                        leptos::logging::error!("audit log migration failed: {}", e);
+                        let _ = transaction.abort();
+                    }
+                });
+            })
+        };
+        request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+        settle(&request).await?.dyn_into().map_err(storage_error)
+    }
+
+    /// Rewrites a version 1 store in the chained layout, keeping each key.
+    async fn chain_v1_entries(store: &IdbObjectStore) -> Result<(), AuditError> {
+        let keys: js_sys::Array = settle(&store.get_all_keys().map_err(storage_error)?).await?.unchecked_into();
+        let values: js_sys::Array = settle(&store.get_all().map_err(storage_error)?).await?.unchecked_into();
+        let stored = keys
+            .iter()
+            .zip(values.iter())
+            .map(|(key, value)| (key.as_f64().unwrap_or_default() as u64, value.as_string().unwrap_or_default()))
+            .collect();
+        for entry in upgrade_legacy(stored)? {
+            let json = JsValue::from_str(&encode(&entry)?);
+            settle(&store.put_with_key(&json, &(entry.seq as f64).into()).map_err(storage_error)?).await?;
+        }
+        Ok(())
+    }
+
+    /// The audit log in the origin's IndexedDB, keyed by sequence number.
+    /// The database opens on first use.
+    pub struct IndexedDbAuditStore {
+        name: String,
+        db: RefCell<Option<IdbDatabase>>,
+    }
+
+    impl IndexedDbAuditStore {
+        pub fn new(name: &str) -> Self {
+            Self { name: name.into(), db: RefCell::new(None) }
+        }
+
+        async fn db(&self) -> Result<IdbDatabase, AuditError> {
+            if let Some(db) = self.db.borrow().clone() {
+                return Ok(db);
+            }
+            let db = open(&self.name).await?;
+            *self.db.borrow_mut() = Some(db.clone());
+            Ok(db)
+        }
+
+        /// The object store in a fresh transaction.
+        async fn store(&self, mode: IdbTransactionMode) -> Result<IdbObjectStore, AuditError> {
+            let db = self.db().await?;
+            let transaction = db.transaction_with_str_and_mode(STORE, mode).map_err(storage_error)?;
+            transaction.object_store(STORE).map_err(storage_error)
+        }
+    }
+
+    /// The entry under `cursor`, or `None` once it has run off the end.
+    fn entry_at(step: JsValue) -> Result<Option<(IdbCursorWithValue, AuditEntry)>, AuditError> {
+        let Ok(cursor) = step.dyn_into::<IdbCursorWithValue>() else { return Ok(None) };
+        let seq = cursor.key().ok().and_then(|k| k.as_f64()).unwrap_or_default() as u64;
+        let json = cursor.value().ok().and_then(|v| v.as_string()).unwrap_or_default();
+        let entry = decode(seq, &json)?;
+        Ok(Some((cursor, entry)))
+    }
+
+    fn newest_first(store: &IdbObjectStore, range: &JsValue) -> Result<IdbRequest, AuditError> {
+        store.open_cursor_with_range_and_direction(range, IdbCursorDirection::Prev).map_err(storage_error)
+    }
+
+    impl AuditStore for IndexedDbAuditStore {
+        fn append<'a>(&'a self, attestation: &'a IntentAttestation) -> LocalBoxFuture<'a, Result<AuditEntry, AuditError>> {
+            Box::pin(async move {
+                // Reading the head and adding after it share one transaction, so
+                // two tabs appending at once cannot both link to the same entry.
+                let store = self.store(IdbTransactionMode::Readwrite).await?;
+                let newest = entry_at(settle(&newest_first(&store, &JsValue::UNDEFINED)?).await?)?.map(|(_, e)| e);
+                let entry = next_entry(newest.as_ref(), attestation)?;
+                let json = JsValue::from_str(&encode(&entry)?);
+                let request = store.add_with_key(&json, &(entry.seq as f64).into()).map_err(storage_error)?;
+                settle(&request).await?;
+                Ok(entry)
+            })
+        }
+
+        fn page(&self, before: Option<u64>, limit: usize) -> LocalBoxFuture<'_, Result<Page, AuditError>> {
+            Box::pin(async move {
+                let range = match before {
+                    Some(before) => Some(IdbKeyRange::upper_bound_with_open(&(before as f64).into(), true).map_err(storage_error)?),
+                    None => None,
+                };
+                let store = self.store(IdbTransactionMode::Readonly).await?;
+                let request = newest_first(&store, range.as_ref().map_or(&JsValue::UNDEFINED, |r| r.as_ref()))?;
+                let mut read = Vec::new();
+                while let Some((cursor, entry)) = entry_at(settle(&request).await?)? {
+                    read.push(entry);
+                    if read.len() > limit {
+                        break;
+                    }
+                    cursor.continue_().map_err(storage_error)?;
+                }
+                Ok(paginate(read, limit))
+            })
+        }
+
+        fn count(&self) -> LocalBoxFuture<'_, Result<u64, AuditError>> {
+            Box::pin(async move {
+                let store = self.store(IdbTransactionMode::Readonly).await?;
+                let request = store.count().map_err(storage_error)?;
+                let count = settle(&request).await?;
+                Ok(count.as_f64().unwrap_or_default() as u64)
+            })
+        }
+    }
+}
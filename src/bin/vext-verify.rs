@@ -0,0 +1,227 @@
+//! Offline verifier for VEXT intent attestations.
+//!
+//! Usage: vext-verify [--key <HEX>] [--ttl <SECS> | --no-ttl] [--now <UNIX>] [--min-hold <MS>] [--require-wallet-sig]
+//!                    [--allow-legacy] [--max-quote-age <MS>] [--credential <FILE> --rp-id <ID> [--origin <URL>]... [--sign-count <N>]]
+//!                    <FILE>...
+//!
+//! Each FILE holds one `IntentAttestation` object, an array of them, JSON Lines
+//! with one per line, or a signed audit log bundle; attestations may be in any
+//! stored schema version and older layouts are upgraded before verification.
+//! Schema 1 records, whose signature covers only part of the body, fail unless
+//! `--allow-legacy` is given.
+//! A bundle is checked as a whole first (manifest signature, record digest,
+//! hash chain, checkpoint root and inclusion proofs) and then record by record;
+//! its records must all be sealed by the bundle signer. Within a file a
+//! nonce may only be used once, and bundle records must each link to the last.
+//! With `--credential` (a `CredentialRecord` JSON file) every `biometric_proof`
+//! must be a WebAuthn assertion by that credential over the record's body hash.
+//! A wallet co-signature is checked whenever present; `--require-wallet-sig`
+//! also fails records without one. `--max-quote-age` requires a bound price
+//! quote no older than the limit when the record was sealed.
+//! Without `--key` records are checked against the signer they name
+//! themselves, which proves nothing about who wrote them: they are reported as
+//! UNAUTHENTICATED rather than PASS.
+//! Exits 0 when every record verifies against `--key`, 1 when any fails, 2 on
+//! usage errors and 3 when everything checks out but no `--key` was given.
+
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ed25519_dalek::VerifyingKey;
+use vext_vault::import::{self, AttestationFile};
+use vext_vault::verify::Policy;
+use vext_vault::webauthn::CredentialRecord;
+
+const USAGE: &str = "usage: vext-verify [--key <HEX>] [--ttl <SECS> | --no-ttl] [--now <UNIX>] [--min-hold <MS>] [--require-wallet-sig]
+                   [--allow-legacy] [--max-quote-age <MS>] [--credential <FILE> --rp-id <ID> [--origin <URL>]... [--sign-count <N>]]
+                   <FILE>...";
+
+/// Schema 1 signed only asset, nonce, timestamp, wallet, hold duration and
+/// entropy; nothing else in a legacy record is attested.
+const LEGACY_NOTE: &str = "  (legacy: only asset, nonce, timestamp, wallet, hold and entropy signed)";
+
+struct Args {
+    trusted_key: Option<VerifyingKey>,
+    policy: Policy,
+    files: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?
+        .as_secs();
+    let mut args = Args { trusted_key: None, policy: Policy::new(now), files: Vec::new() };
+    let (mut credential, mut rp_id, mut origins, mut sign_count) = (None, None, Vec::new(), 0u32);
+
+    let mut it = std::env::args().skip(1);
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--key" => {
+                let hex_key = it.next().ok_or("--key needs a value")?;
+                let bytes: [u8; 32] = hex::decode(&hex_key)
+                    .ok()
+                    .and_then(|b| b.try_into().ok())
+                    .ok_or("--key must be 32 hex-encoded bytes")?;
+                let key = VerifyingKey::from_bytes(&bytes).map_err(|_| "--key is not an Ed25519 point")?;
+                args.trusted_key = Some(key);
+            }
+            "--ttl" => {
+                let secs = it.next().ok_or("--ttl needs a value")?;
+                args.policy.ttl_secs = Some(secs.parse().map_err(|_| "--ttl must be seconds")?);
+            }
+            "--no-ttl" => args.policy.ttl_secs = None,
+            "--now" => {
+                let secs = it.next().ok_or("--now needs a value")?;
+                args.policy.now_utc = secs.parse().map_err(|_| "--now must be a Unix timestamp")?;
+            }
+            "--min-hold" => {
+                let ms = it.next().ok_or("--min-hold needs a value")?;
+                args.policy.min_hold_ms = Some(ms.parse().map_err(|_| "--min-hold must be milliseconds")?);
+            }
+            "--require-wallet-sig" => args.policy.require_wallet_signature = true,
+            "--allow-legacy" => args.policy.allow_legacy_schema = true,
+            "--max-quote-age" => {
+                let ms = it.next().ok_or("--max-quote-age needs a value")?;
+                args.policy.max_quote_age_ms = Some(ms.parse().map_err(|_| "--max-quote-age must be milliseconds")?);
+            }
+            "--credential" => {
+                let path = it.next().ok_or("--credential needs a value")?;
+                let raw = std::fs::read_to_string(&path).map_err(|e| format!("--credential: {}", e))?;
+                let record: CredentialRecord =
+                    serde_json::from_str(&raw).map_err(|e| format!("--credential: {}", e))?;
+                credential = Some(record);
+            }
+            "--rp-id" => rp_id = Some(it.next().ok_or("--rp-id needs a value")?),
+            "--origin" => origins.push(it.next().ok_or("--origin needs a value")?),
+            "--sign-count" => {
+                let n = it.next().ok_or("--sign-count needs a value")?;
+                sign_count = n.parse().map_err(|_| "--sign-count must be a counter value")?;
+            }
+            "-h" | "--help" => return Err(String::new()),
+            flag if flag.starts_with("--") => return Err(format!("unknown option {}", flag)),
+            file => args.files.push(file.to_string()),
+        }
+    }
+    if args.files.is_empty() {
+        return Err("no input files".into());
+    }
+    if let Some(record) = credential {
+        let rp_id = rp_id.ok_or("--credential needs --rp-id")?;
+        let mut registered = record.register(&rp_id).map_err(|e| format!("--credential: {}", e))?;
+        if !origins.is_empty() {
+            registered.allowed_origins = origins;
+        }
+        registered.sign_count = sign_count;
+        args.policy.credential = Some(registered);
+    } else if rp_id.is_some() || !origins.is_empty() {
+        return Err("--rp-id and --origin need --credential".into());
+    }
+    Ok(args)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(why) => {
+            if !why.is_empty() {
+                eprintln!("error: {}", why);
+            }
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    if args.trusted_key.is_none() {
+        eprintln!("warning: no --key given; checking against each record's embedded signer_pubkey");
+    }
+
+    let (mut passed, mut failed) = (0usize, 0usize);
+    for path in &args.files {
+        let parsed = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|raw| AttestationFile::parse(&raw).map_err(|e| e.to_string()));
+        let file = match parsed {
+            Ok(file) => file,
+            Err(why) => {
+                println!("FAIL  {}  unreadable: {}", path, why);
+                failed += 1;
+                continue;
+            }
+        };
+        let report = import::check_file(&file, args.trusted_key.as_ref(), &args.policy);
+        // Without --key a file only vouches for itself: a forger's own key passes.
+        let pass = if report.authenticated { "PASS" } else { "UNAUTHENTICATED" };
+        if let (AttestationFile::Bundle(bundle), Some(verdict)) = (&file, &report.bundle) {
+            match verdict {
+                Ok(()) => {
+                    passed += 1;
+                    println!(
+                        "{}  {}  bundle origin={} records={} head={}{}",
+                        pass,
+                        path,
+                        bundle.manifest.origin,
+                        bundle.manifest.record_count,
+                        bundle.manifest.chain_head,
+                        bundle
+                            .manifest
+                            .checkpoint
+                            .as_ref()
+                            .map(|c| format!(
+                                " checkpoint={}@{} proofs={}",
+                                c.checkpoint.root_hash,
+                                c.checkpoint.tree_size,
+                                bundle.proofs.len()
+                            ))
+                            .unwrap_or_default()
+                    );
+                }
+                Err(why) => {
+                    failed += 1;
+                    println!("FAIL  {}  bundle: {}", path, why);
+                }
+            }
+        }
+        for record in &report.records {
+            let (i, attestation) = (record.index, &record.attestation);
+            match (&record.verified, record.passed()) {
+                (Some(ok), true) => {
+                    passed += 1;
+                    println!(
+                        "{}  {}[{}]  v{} asset={} nonce={} signer={} age={}s{}{}{}{}",
+                        pass,
+                        path,
+                        i,
+                        ok.schema_version,
+                        attestation.body.asset_symbol,
+                        ok.nonce,
+                        hex::encode(ok.signer.to_bytes()),
+                        ok.age_secs,
+                        ok.sign_count.map(|n| format!(" bio-count={}", n)).unwrap_or_default(),
+                        if ok.wallet_cosigned { " wallet=cosigned" } else { "" },
+                        attestation
+                            .body
+                            .quote
+                            .as_ref()
+                            .zip(ok.quote_age_ms)
+                            .map(|(q, age)| format!(" quote={}{}@{}ms", q.price, q.currency, age))
+                            .unwrap_or_default(),
+                        if ok.schema_version == 1 { LEGACY_NOTE } else { "" }
+                    );
+                }
+                _ => {
+                    failed += 1;
+                    let reasons: Vec<String> = record.issues.iter().map(ToString::to_string).collect();
+                    println!("FAIL  {}[{}]  {}", path, i, reasons.join("; "));
+                }
+            }
+        }
+    }
+
+    let authenticated = args.trusted_key.is_some();
+    println!("{} {}, {} failed", passed, if authenticated { "passed" } else { "unauthenticated" }, failed);
+    match (failed, authenticated) {
+        (0, true) => ExitCode::SUCCESS,
+        (0, false) => ExitCode::from(3),
+        _ => ExitCode::FAILURE,
+    }
+}
@@ -0,0 +1,422 @@
+use std::cell::Cell;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as B64URL, Engine};
+use futures::future::{self, LocalBoxFuture};
+use p256::ecdsa::{signature::Signer, DerSignature, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::jcs;
+
+/* ===================== BIOMETRIC USER VERIFICATION ===================== */
+
+/// Authenticator data flag: the user was present (touched the authenticator).
+pub const FLAG_USER_PRESENT: u8 = 0x01;
+/// Authenticator data flag: the user was verified (biometric or device PIN).
+pub const FLAG_USER_VERIFIED: u8 = 0x04;
+
+/// rpIdHash (32) + flags (1) + signCount (4).
+const AUTH_DATA_MIN_LEN: usize = 37;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BiometricError {
+    /// No user-verifying platform authenticator on this device.
+    Unsupported,
+    /// The user dismissed the prompt or it timed out.
+    Cancelled,
+    /// The authenticator answered without performing user verification.
+    NotVerified,
+    /// The assertion or stored proof is malformed.
+    Malformed(String),
+    /// The platform rejected the request for another reason.
+    Failed(String),
+}
+
+impl std::fmt::Display for BiometricError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BiometricError::Unsupported => write!(f, "no platform authenticator available"),
+            BiometricError::Cancelled => write!(f, "verification cancelled"),
+            BiometricError::NotVerified => write!(f, "user not verified"),
+            BiometricError::Malformed(why) => write!(f, "malformed assertion: {}", why),
+            BiometricError::Failed(why) => write!(f, "verification failed: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for BiometricError {}
+
+/// A WebAuthn assertion, every field base64url without padding. Its canonical
+/// JSON is what lands in `AttestationBody::biometric_proof`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BiometricAssertion {
+    pub credential_id: String,
+    pub authenticator_data: String,
+    pub client_data_json: String,
+    pub signature: String,
+}
+
+impl BiometricAssertion {
+    pub fn from_parts(credential_id: &[u8], authenticator_data: &[u8], client_data_json: &[u8], signature: &[u8]) -> Self {
+        Self {
+            credential_id: B64URL.encode(credential_id),
+            authenticator_data: B64URL.encode(authenticator_data),
+            client_data_json: B64URL.encode(client_data_json),
+            signature: B64URL.encode(signature),
+        }
+    }
+
+    /// Serialized form carried in `biometric_proof`.
+    pub fn to_proof(&self) -> String {
+        let bytes = jcs::to_canonical_vec(self).expect("assertion holds only strings");
+        String::from_utf8(bytes).expect("canonical JSON is UTF-8")
+    }
+
+    pub fn from_proof(proof: &str) -> Result<Self, BiometricError> {
+        serde_json::from_str(proof).map_err(|e| BiometricError::Malformed(e.to_string()))
+    }
+
+    pub fn authenticator_data_bytes(&self) -> Result<Vec<u8>, BiometricError> {
+        let raw = decode_field("authenticator_data", &self.authenticator_data)?;
+        if raw.len() < AUTH_DATA_MIN_LEN {
+            return Err(BiometricError::Malformed("authenticator_data too short".into()));
+        }
+        Ok(raw)
+    }
+
+    pub fn client_data_bytes(&self) -> Result<Vec<u8>, BiometricError> {
+        decode_field("client_data_json", &self.client_data_json)
+    }
+
+    pub fn signature_bytes(&self) -> Result<Vec<u8>, BiometricError> {
+        decode_field("signature", &self.signature)
+    }
+
+    /// The authenticator data flags byte.
+    pub fn flags(&self) -> Result<u8, BiometricError> {
+        Ok(self.authenticator_data_bytes()?[32])
+    }
+
+    pub fn user_verified(&self) -> Result<bool, BiometricError> {
+        Ok(self.flags()? & FLAG_USER_VERIFIED != 0)
+    }
+
+    /// Checks that this is a user-verified `webauthn.get` over `challenge`.
+    /// The signature is not checked here; that takes the credential's public
+    /// key, see `webauthn::RegisteredCredential::verify`.
+    pub fn answers(&self, challenge: &[u8]) -> Result<(), BiometricError> {
+        let client_data: CollectedClientData = serde_json::from_slice(&self.client_data_bytes()?)
+            .map_err(|e| BiometricError::Malformed(format!("clientDataJSON: {}", e)))?;
+        if client_data.kind != "webauthn.get" || B64URL.decode(&client_data.challenge).ok().as_deref() != Some(challenge) {
+            return Err(BiometricError::Malformed("not an assertion over this challenge".into()));
+        }
+        if !self.user_verified()? {
+            return Err(BiometricError::NotVerified);
+        }
+        Ok(())
+    }
+}
+
+/// The `clientDataJSON` an authenticator signs over, in browser field order.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectedClientData {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub challenge: String,
+    pub origin: String,
+    #[serde(default)]
+    pub cross_origin: bool,
+}
+
+/// A source of user-verified assertions over a caller-chosen challenge.
+pub trait BiometricProvider {
+    fn verify_user<'a>(&'a self, challenge: &'a [u8]) -> LocalBoxFuture<'a, Result<BiometricAssertion, BiometricError>>;
+}
+
+fn decode_field(name: &str, value: &str) -> Result<Vec<u8>, BiometricError> {
+    B64URL.decode(value).map_err(|_| BiometricError::Malformed(format!("{} is not base64url", name)))
+}
+
+/* ===================== DETERMINISTIC MOCK ===================== */
+
+/// In-process authenticator for tests and native tooling. Keys derive from a seed
+/// and ECDSA is RFC 6979, so the same seed and challenge give the same assertion.
+pub struct MockBiometric {
+    signing_key: SigningKey,
+    credential_id: Vec<u8>,
+    rp_id: String,
+    origin: String,
+    flags: u8,
+    sign_count: Cell<u32>,
+    refusal: Option<BiometricError>,
+}
+
+impl MockBiometric {
+    pub fn from_seed(seed: &[u8]) -> Self {
+        let scalar = Sha256::digest([b"vext.mock-biometric.key".as_slice(), seed].concat());
+        let signing_key = SigningKey::from_slice(&scalar).expect("SHA-256 output is a valid P-256 scalar");
+        let credential_id = Sha256::digest([b"vext.mock-biometric.id".as_slice(), seed].concat()).to_vec();
+        Self {
+            signing_key,
+            credential_id,
+            rp_id: "localhost".into(),
+            origin: "http://localhost:8080".into(),
+            flags: FLAG_USER_PRESENT | FLAG_USER_VERIFIED,
+            sign_count: Cell::new(0),
+            refusal: None,
+        }
+    }
+
+    pub fn with_origin(mut self, rp_id: &str, origin: &str) -> Self {
+        self.rp_id = rp_id.into();
+        self.origin = origin.into();
+        self
+    }
+
+    /// Overrides the authenticator data flags, e.g. to drop `FLAG_USER_VERIFIED`.
+    pub fn with_flags(mut self, flags: u8) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Every `verify_user` call fails with `err`.
+    pub fn refusing(mut self, err: BiometricError) -> Self {
+        self.refusal = Some(err);
+        self
+    }
+
+    pub fn credential_id(&self) -> &[u8] {
+        &self.credential_id
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        *self.signing_key.verifying_key()
+    }
+
+    fn assert(&self, challenge: &[u8]) -> Result<BiometricAssertion, BiometricError> {
+        if let Some(err) = &self.refusal {
+            return Err(err.clone());
+        }
+        let count = self.sign_count.get() + 1;
+        self.sign_count.set(count);
+
+        let mut auth_data = Sha256::digest(self.rp_id.as_bytes()).to_vec();
+        auth_data.push(self.flags);
+        auth_data.extend_from_slice(&count.to_be_bytes());
+
+        let client_data = serde_json::to_vec(&CollectedClientData {
+            kind: "webauthn.get".into(),
+            challenge: B64URL.encode(challenge),
+            origin: self.origin.clone(),
+            cross_origin: false,
+        })
+        .map_err(|e| BiometricError::Failed(e.to_string()))?;
+
+        let signed = [auth_data.as_slice(), Sha256::digest(&client_data).as_slice()].concat();
+        let signature: DerSignature = self.signing_key.sign(&signed);
+        Ok(BiometricAssertion::from_parts(&self.credential_id, &auth_data, &client_data, signature.as_bytes()))
+    }
+}
+
+impl BiometricProvider for MockBiometric {
+    fn verify_user<'a>(&'a self, challenge: &'a [u8]) -> LocalBoxFuture<'a, Result<BiometricAssertion, BiometricError>> {
+        Box::pin(future::ready(self.assert(challenge)))
+    }
+}
+
+/* ===================== WEBAUTHN PLATFORM AUTHENTICATOR ===================== */
+
+/// localStorage slot holding the registered credential's `CredentialRecord`.
+#[cfg(target_arch = "wasm32")]
+pub const CREDENTIAL_STORAGE_KEY: &str = "vext.webauthn_credential";
+
+#[cfg(target_arch = "wasm32")]
+use crate::webauthn::CredentialRecord;
+
+#[cfg(target_arch = "wasm32")]
+const WEBAUTHN_TIMEOUT_MS: u32 = 60_000;
+
+/// Platform authenticator (Touch ID, Windows Hello, Android biometrics) with
+/// `userVerification: "required"`. Registers a credential on first use.
+#[cfg(target_arch = "wasm32")]
+pub struct WebAuthnProvider {
+    rp_id: String,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl WebAuthnProvider {
+    /// Relying party bound to the page's own hostname.
+    pub fn for_current_origin() -> Result<Self, BiometricError> {
+        let rp_id = web_sys::window()
+            .and_then(|w| w.location().hostname().ok())
+            .ok_or_else(|| BiometricError::Failed("no window location".into()))?;
+        Ok(Self { rp_id })
+    }
+
+    async fn assert(&self, challenge: &[u8]) -> Result<BiometricAssertion, BiometricError> {
+        use wasm_bindgen::JsCast;
+
+        let available = browser::await_promise(web_sys::PublicKeyCredential::is_user_verifying_platform_authenticator_available()).await?;
+        if available.as_bool() != Some(true) {
+            return Err(BiometricError::Unsupported);
+        }
+        let record = match browser::stored_credential() {
+            Some(record) => record,
+            None => self.register().await?,
+        };
+        let credential_id = record
+            .credential_id_bytes()
+            .map_err(|e| BiometricError::Malformed(e.to_string()))?;
+
+        let descriptor = browser::object(&[
+            ("type", "public-key".into()),
+            ("id", js_sys::Uint8Array::from(credential_id.as_slice()).into()),
+        ]);
+        let options = browser::object(&[(
+            "publicKey",
+            browser::object(&[
+                ("challenge", js_sys::Uint8Array::from(challenge).into()),
+                ("rpId", self.rp_id.as_str().into()),
+                ("allowCredentials", js_sys::Array::of1(&descriptor).into()),
+                ("userVerification", "required".into()),
+                ("timeout", WEBAUTHN_TIMEOUT_MS.into()),
+            ]),
+        )]);
+        let promise = browser::credentials()?
+            .get_with_options(options.unchecked_ref())
+            .map_err(browser::classify)?;
+        let credential: web_sys::PublicKeyCredential = browser::await_promise(promise).await?.unchecked_into();
+        let response: web_sys::AuthenticatorAssertionResponse = credential.response().unchecked_into();
+
+        let assertion = BiometricAssertion::from_parts(
+            &js_sys::Uint8Array::new(&credential.raw_id()).to_vec(),
+            &js_sys::Uint8Array::new(&response.authenticator_data()).to_vec(),
+            &js_sys::Uint8Array::new(&response.client_data_json()).to_vec(),
+            &js_sys::Uint8Array::new(&response.signature()).to_vec(),
+        );
+        if !assertion.user_verified()? {
+            return Err(BiometricError::NotVerified);
+        }
+        Ok(assertion)
+    }
+
+    /// The credential registered in this browser, for enrolling with a verifier.
+    pub fn credential(&self) -> Option<CredentialRecord> {
+        browser::stored_credential()
+    }
+
+    /// Creates a non-discoverable ES256 platform credential and remembers it.
+    async fn register(&self) -> Result<CredentialRecord, BiometricError> {
+        use wasm_bindgen::JsCast;
+
+        let mut user_id = [0u8; 16];
+        let mut challenge = [0u8; 32];
+        getrandom::getrandom(&mut user_id).map_err(|e| BiometricError::Failed(e.to_string()))?;
+        getrandom::getrandom(&mut challenge).map_err(|e| BiometricError::Failed(e.to_string()))?;
+
+        let options = browser::object(&[(
+            "publicKey",
+            browser::object(&[
+                ("rp", browser::object(&[("id", self.rp_id.as_str().into()), ("name", "VEXT Vault".into())])),
+                (
+                    "user",
+                    browser::object(&[
+                        ("id", js_sys::Uint8Array::from(user_id.as_slice()).into()),
+                        ("name", "vext-device".into()),
+                        ("displayName", "VEXT Device".into()),
+                    ]),
+                ),
+                ("challenge", js_sys::Uint8Array::from(challenge.as_slice()).into()),
+                (
+                    "pubKeyCredParams",
+                    js_sys::Array::of1(&browser::object(&[("type", "public-key".into()), ("alg", (-7).into())])).into(),
+                ),
+                (
+                    "authenticatorSelection",
+                    browser::object(&[
+                        ("authenticatorAttachment", "platform".into()),
+                        ("residentKey", "discouraged".into()),
+                        ("userVerification", "required".into()),
+                    ]),
+                ),
+                ("attestation", "none".into()),
+                ("timeout", WEBAUTHN_TIMEOUT_MS.into()),
+            ]),
+        )]);
+        let promise = browser::credentials()?
+            .create_with_options(options.unchecked_ref())
+            .map_err(browser::classify)?;
+        let credential: web_sys::PublicKeyCredential = browser::await_promise(promise).await?.unchecked_into();
+        let response: web_sys::AuthenticatorAttestationResponse = credential.response().unchecked_into();
+        let attestation_object = js_sys::Uint8Array::new(&response.attestation_object()).to_vec();
+        let (id, cose_key) = crate::webauthn::parse_attestation_object(&attestation_object)
+            .map_err(|e| BiometricError::Malformed(e.to_string()))?;
+        let record = CredentialRecord::new(&id, &cose_key);
+        browser::store_credential(&record);
+        Ok(record)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl BiometricProvider for WebAuthnProvider {
+    fn verify_user<'a>(&'a self, challenge: &'a [u8]) -> LocalBoxFuture<'a, Result<BiometricAssertion, BiometricError>> {
+        Box::pin(self.assert(challenge))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use super::{BiometricError, CREDENTIAL_STORAGE_KEY};
+    use crate::webauthn::CredentialRecord;
+    use js_sys::{Object, Promise, Reflect};
+    use wasm_bindgen::{JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+
+    pub fn object(entries: &[(&str, JsValue)]) -> JsValue {
+        let obj = Object::new();
+        for (key, value) in entries {
+            let _ = Reflect::set(&obj, &(*key).into(), value);
+        }
+        obj.into()
+    }
+
+    pub fn credentials() -> Result<web_sys::CredentialsContainer, BiometricError> {
+        web_sys::window()
+            .map(|w| w.navigator().credentials())
+            .ok_or(BiometricError::Unsupported)
+    }
+
+    pub async fn await_promise(promise: Promise) -> Result<JsValue, BiometricError> {
+        JsFuture::from(promise).await.map_err(classify)
+    }
+
+    /// Maps a rejected WebAuthn call onto `BiometricError` by DOMException name.
+    pub fn classify(err: JsValue) -> BiometricError {
+        let name = err.dyn_ref::<js_sys::Error>().map(|e| String::from(e.name())).unwrap_or_default();
+        match name.as_str() {
+            "NotAllowedError" | "AbortError" => BiometricError::Cancelled,
+            "NotSupportedError" => BiometricError::Unsupported,
+            _ => BiometricError::Failed(
+                err.dyn_ref::<js_sys::Error>()
+                    .map(|e| String::from(e.message()))
+                    .unwrap_or_else(|| format!("{:?}", err)),
+            ),
+        }
+    }
+
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window().and_then(|w| w.local_storage().ok().flatten())
+    }
+
+    pub fn stored_credential() -> Option<CredentialRecord> {
+        let raw = storage()?.get_item(CREDENTIAL_STORAGE_KEY).ok().flatten()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    pub fn store_credential(record: &CredentialRecord) {
+        if let (Some(s), Ok(raw)) = (storage(), serde_json::to_string(record)) {
+            let _ = s.set_item(CREDENTIAL_STORAGE_KEY, &raw);
+        }
+    }
+}
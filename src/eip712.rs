@@ -0,0 +1,180 @@
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+
+use crate::attestation::AttestationBody;
+use crate::jcs::JcsError;
+
+/* ===================== EIP-712 TYPED-DATA ATTESTATION ===================== */
+
+// An Ethereum wallet co-signs the intent as EIP-712 typed data, so the wallet
+// shows the asset, nonce and hold instead of an opaque hex blob. `bodyHash`
+// commits to the complete canonical body; the other members are for display.
+
+pub const DOMAIN_NAME: &str = "VEXT Vault";
+pub const DOMAIN_VERSION: &str = "1";
+
+const DOMAIN_TYPE: &str = "EIP712Domain(string name,string version,uint256 chainId)";
+const INTENT_TYPE: &str =
+    "VextIntent(string context,string asset,address wallet,string nonce,uint64 timestamp,uint64 holdDurationMs,bytes32 bodyHash)";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Eip712Error {
+    /// `wallet_pubkey` is not a 20-byte 0x-prefixed hex address.
+    MalformedAddress(String),
+    /// The body carries no `wallet_chain_id` to build the domain from.
+    MissingChainId,
+    /// The signature is not 65 bytes `r || s || v` with a low `s`.
+    MalformedSignature,
+    /// No public key could be recovered from the signature.
+    Unrecoverable,
+    Canonicalization(JcsError),
+}
+
+impl std::fmt::Display for Eip712Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Eip712Error::MalformedAddress(addr) => write!(f, "{:?} is not an Ethereum address", addr),
+            Eip712Error::MissingChainId => write!(f, "wallet_chain_id missing"),
+            Eip712Error::MalformedSignature => write!(f, "signature is not 65 bytes r||s||v"),
+            Eip712Error::Unrecoverable => write!(f, "no signer recoverable from signature"),
+            Eip712Error::Canonicalization(e) => write!(f, "cannot canonicalize body: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Eip712Error {}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Parses a 0x-prefixed hex address, in any letter case.
+pub fn parse_address(address: &str) -> Result<[u8; 20], Eip712Error> {
+    address
+        .strip_prefix("0x")
+        .and_then(|h| hex::decode(h).ok())
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| Eip712Error::MalformedAddress(address.to_string()))
+}
+
+/// The address of a secp256k1 key: the last 20 bytes of keccak256(x || y).
+pub fn address_of(key: &VerifyingKey) -> [u8; 20] {
+    let point = key.to_encoded_point(false);
+    let hash = keccak256(&point.as_bytes()[1..]);
+    hash[12..].try_into().expect("20-byte suffix of a 32-byte hash")
+}
+
+fn word_u64(n: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&n.to_be_bytes());
+    word
+}
+
+fn word_address(address: &[u8; 20]) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(address);
+    word
+}
+
+fn domain_separator(chain_id: u64) -> [u8; 32] {
+    let encoded = [
+        keccak256(DOMAIN_TYPE.as_bytes()),
+        keccak256(DOMAIN_NAME.as_bytes()),
+        keccak256(DOMAIN_VERSION.as_bytes()),
+        word_u64(chain_id),
+    ]
+    .concat();
+    keccak256(&encoded)
+}
+
+/// The members of the signed `VextIntent` struct.
+struct Intent {
+    chain_id: u64,
+    wallet: [u8; 20],
+    body_hash: [u8; 32],
+}
+
+fn intent(body: &AttestationBody) -> Result<Intent, Eip712Error> {
+    Ok(Intent {
+        chain_id: body.wallet_chain_id.ok_or(Eip712Error::MissingChainId)?,
+        wallet: parse_address(&body.wallet_pubkey)?,
+        body_hash: keccak256(&body.signing_message().map_err(Eip712Error::Canonicalization)?),
+    })
+}
+
+/// The EIP-712 digest an Ethereum wallet signs for `body`.
+pub fn signing_digest(body: &AttestationBody) -> Result<[u8; 32], Eip712Error> {
+    let intent = intent(body)?;
+    let struct_hash = keccak256(
+        &[
+            keccak256(INTENT_TYPE.as_bytes()),
+            keccak256(body.context.as_bytes()),
+            keccak256(body.asset_symbol.as_bytes()),
+            word_address(&intent.wallet),
+            keccak256(body.nonce.as_bytes()),
+            word_u64(body.timestamp_utc),
+            word_u64(body.hold_duration_ms),
+            intent.body_hash,
+        ]
+        .concat(),
+    );
+    Ok(keccak256(&[&[0x19, 0x01][..], &domain_separator(intent.chain_id), &struct_hash].concat()))
+}
+
+/// The `eth_signTypedData_v4` payload whose digest is `signing_digest(body)`.
+pub fn typed_data(body: &AttestationBody) -> Result<Value, Eip712Error> {
+    let intent = intent(body)?;
+    Ok(json!({
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+            ],
+            "VextIntent": [
+                { "name": "context", "type": "string" },
+                { "name": "asset", "type": "string" },
+                { "name": "wallet", "type": "address" },
+                { "name": "nonce", "type": "string" },
+                { "name": "timestamp", "type": "uint64" },
+                { "name": "holdDurationMs", "type": "uint64" },
+                { "name": "bodyHash", "type": "bytes32" },
+            ],
+        },
+        "primaryType": "VextIntent",
+        "domain": { "name": DOMAIN_NAME, "version": DOMAIN_VERSION, "chainId": intent.chain_id },
+        "message": {
+            "context": body.context,
+            "asset": body.asset_symbol,
+            "wallet": format!("0x{}", hex::encode(intent.wallet)),
+            "nonce": body.nonce,
+            "timestamp": body.timestamp_utc,
+            "holdDurationMs": body.hold_duration_ms,
+            "bodyHash": format!("0x{}", hex::encode(intent.body_hash)),
+        },
+    }))
+}
+
+/// Recovers the signing address from a 65-byte `r || s || v` signature over `digest`.
+/// `v` must be 27/28 or 0/1; high-`s` signatures are rejected as malleable.
+pub fn recover_address(digest: &[u8; 32], signature: &[u8]) -> Result<[u8; 20], Eip712Error> {
+    let (rs, v) = match signature {
+        [rs @ .., v] if rs.len() == 64 => (rs, *v),
+        _ => return Err(Eip712Error::MalformedSignature),
+    };
+    let signature = Signature::from_slice(rs).map_err(|_| Eip712Error::MalformedSignature)?;
+    if signature.normalize_s().is_some() {
+        return Err(Eip712Error::MalformedSignature);
+    }
+    // Ids 2 and 3 (v = 29/30) mean r overflowed the group order; wallets never produce them.
+    let recovery_id = match v {
+        0 | 1 => RecoveryId::from_byte(v),
+        27 | 28 => RecoveryId::from_byte(v - 27),
+        _ => None,
+    }
+    .ok_or(Eip712Error::MalformedSignature)?;
+    let key = VerifyingKey::recover_from_prehash(digest, &signature, recovery_id)
+        .map_err(|_| Eip712Error::Unrecoverable)?;
+    Ok(address_of(&key))
+}
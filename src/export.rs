@@ -0,0 +1,333 @@
+/* ===================== AUDIT LOG EXPORT ===================== */
+
+// Three ways out of the browser, all oldest entry first:
+//   * JSON Lines: one full `IntentAttestation` per line, for tooling.
+//   * CSV: one flat row per attestation, for finance.
+//   * Signed bundle: every chained entry plus a manifest (count, record digest,
+//     chain head, latest checkpoint) signed by the device key, and an
+//     inclusion proof under the checkpoint for each record it covers, so one
+//     record can be shown to be logged without the rest. `vext-verify` checks
+//     a bundle end to end: manifest signature, record digest, hash chain,
+//     checkpoint root and proofs, then every attestation.
+
+use ed25519_dalek::{Signature, VerifyingKey, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::audit::{verify_chain, AuditEntry, ChainBreak};
+use crate::jcs::{self, JcsError};
+use crate::keys::DeviceKey;
+use crate::merkle::{InclusionProof, MerkleError, MerkleTree, SignedCheckpoint};
+use crate::verify::{self, VerifyError};
+
+/// `manifest.format` of bundles this module writes and reads.
+pub const BUNDLE_FORMAT: &str = "vext-bundle/1";
+
+/// Domain separator for bundle signatures.
+pub const BUNDLE_CONTEXT: &str = "VEXT-BUNDLE-V1";
+
+pub const CSV_HEADER: &str = "seq,timestamp_utc,asset,price,currency,quoted_at_ms,wallet,wallet_chain_id,\
+hold_duration_ms,nonce,signer_pubkey,signature,wallet_signature";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BundleError {
+    Canonicalization(String),
+    /// `signer_pubkey` is not a valid hex Ed25519 key.
+    MalformedSignerKey,
+    /// `manifest.format` is not `BUNDLE_FORMAT`.
+    UnsupportedFormat(String),
+    /// The bundle is signed by a key other than the trusted one.
+    SignerMismatch { expected: String, found: String },
+    MalformedSignature,
+    BadSignature,
+    /// The records are not the ones the manifest commits to.
+    DigestMismatch,
+    CountMismatch { manifest: u64, records: u64 },
+    /// The records do not form an unbroken chain from the first entry.
+    Chain(ChainBreak),
+    /// The records chain, but not to the head the manifest names.
+    HeadMismatch,
+    /// The included checkpoint is not validly signed by the trusted key.
+    Checkpoint(VerifyError),
+    /// The checkpoint is signed, but its tree is not the one the records build.
+    CheckpointMismatch { tree_size: u64, records: u64 },
+    /// There is not one inclusion proof per record the checkpoint covers.
+    ProofCount { tree_size: u64, proofs: u64 },
+    /// The proof next to record `seq` is for another leaf or tree size.
+    MisplacedProof { seq: u64 },
+    /// The inclusion proof of record `seq` does not lead to the checkpoint root.
+    Proof { seq: u64, why: MerkleError },
+}
+
+impl std::fmt::Display for BundleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BundleError::Canonicalization(why) => write!(f, "cannot canonicalize bundle: {}", why),
+            BundleError::MalformedSignerKey => write!(f, "bundle signer_pubkey is not a valid Ed25519 key"),
+            BundleError::UnsupportedFormat(format) => write!(f, "unsupported bundle format {:?}", format),
+            BundleError::SignerMismatch { expected, found } => {
+                write!(f, "bundle signed by {} but {} is trusted", found, expected)
+            }
+            BundleError::MalformedSignature => write!(f, "bundle signature is not 64 hex bytes"),
+            BundleError::BadSignature => write!(f, "bundle signature does not match manifest"),
+            BundleError::DigestMismatch => write!(f, "records do not match manifest digest"),
+            BundleError::CountMismatch { manifest, records } => {
+                write!(f, "manifest lists {} records, bundle holds {}", manifest, records)
+            }
+            BundleError::Chain(brk) => write!(f, "{}", brk),
+            BundleError::HeadMismatch => write!(f, "records do not chain to manifest head"),
+            BundleError::Checkpoint(why) => write!(f, "checkpoint rejected: {}", why),
+            BundleError::CheckpointMismatch { tree_size, records } => {
+                write!(f, "checkpoint over {} leaves does not match the {} records", tree_size, records)
+            }
+            BundleError::ProofCount { tree_size, proofs } => {
+                write!(f, "checkpoint covers {} records but bundle holds {} inclusion proofs", tree_size, proofs)
+            }
+            BundleError::MisplacedProof { seq } => write!(f, "record {}: proof is for another leaf", seq),
+            BundleError::Proof { seq, why } => write!(f, "record {}: {}", seq, why),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+/// One full attestation per line.
+pub fn to_jsonl(entries: &[AuditEntry]) -> Result<String, serde_json::Error> {
+    let mut out = String::new();
+    for entry in entries {
+        out.push_str(&serde_json::to_string(&entry.attestation)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// RFC 4180 field: quoted when it holds a comma, quote or line break. A value
+/// a spreadsheet would run as a formula (leading `=`, `+`, `-`, `@`, tab or
+/// carriage return) is prefixed with `'` and quoted, so it opens as text.
+fn csv_field(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("\"'{}\"", value.replace('"', "\"\""))
+    } else if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One row per attestation under `CSV_HEADER`; absent optional fields are empty.
+pub fn to_csv(entries: &[AuditEntry]) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push_str("\r\n");
+    for entry in entries {
+        let body = &entry.attestation.body;
+        let quote = body.quote.as_ref();
+        let row = [
+            entry.seq.to_string(),
+            body.timestamp_utc.to_string(),
+            body.asset_symbol.clone(),
+            quote.map(|q| q.price.clone()).unwrap_or_default(),
+            quote.map(|q| q.currency.clone()).unwrap_or_default(),
+            quote.map(|q| q.quoted_at_ms.to_string()).unwrap_or_default(),
+            body.wallet_pubkey.clone(),
+            body.wallet_chain_id.map(|id| id.to_string()).unwrap_or_default(),
+            body.hold_duration_ms.to_string(),
+            body.nonce.clone(),
+            body.signer_pubkey.clone(),
+            entry.attestation.signature.clone(),
+            entry.attestation.wallet_signature.clone().unwrap_or_default(),
+        ];
+        out.push_str(&row.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// What a bundle commits to.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BundleManifest {
+    pub format: String,
+    /// Which vault exported it, e.g. its origin.
+    pub origin: String,
+    pub created_ms: u64,
+    pub record_count: u64,
+    /// Hex SHA-256 of the canonical `records` array.
+    pub records_sha256: String,
+    /// Hash of the newest record, as `audit::ChainHead::hash`.
+    pub chain_head: String,
+    /// Latest signed checkpoint over the log, if any; its tree covers the first
+    /// `tree_size` records.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<SignedCheckpoint>,
+}
+
+impl BundleManifest {
+    pub fn signing_message(&self) -> Result<Vec<u8>, JcsError> {
+        jcs::to_canonical_vec(&json!({ "context": BUNDLE_CONTEXT, "manifest": self }))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Bundle {
+    pub manifest: BundleManifest,
+    /// The whole log from sequence number 1, oldest first.
+    pub records: Vec<AuditEntry>,
+    /// Inclusion proof of `records[i]` under the manifest checkpoint, for
+    /// each record the checkpoint covers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub proofs: Vec<InclusionProof>,
+    /// Hex Ed25519 device key that signed the manifest.
+    pub signer_pubkey: String,
+    /// Hex Ed25519 signature over `BundleManifest::signing_message`.
+    pub signature: String,
+}
+
+fn records_digest(records: &[AuditEntry]) -> Result<String, BundleError> {
+    let canonical = jcs::to_canonical_vec(&records).map_err(|e| BundleError::Canonicalization(e.to_string()))?;
+    Ok(hex::encode(Sha256::digest(canonical)))
+}
+
+/// The tree over the records `checkpoint` covers, checked against its root.
+fn checkpoint_tree(records: &[AuditEntry], checkpoint: &SignedCheckpoint) -> Result<MerkleTree, BundleError> {
+    let tree_size = checkpoint.checkpoint.tree_size;
+    let mismatch = BundleError::CheckpointMismatch { tree_size, records: records.len() as u64 };
+    if tree_size > records.len() as u64 {
+        return Err(mismatch);
+    }
+    let mut tree = MerkleTree::new();
+    for entry in &records[..tree_size as usize] {
+        tree.push(&entry.attestation).map_err(|e| BundleError::Canonicalization(e.to_string()))?;
+    }
+    if !hex::encode(tree.root()).eq_ignore_ascii_case(&checkpoint.checkpoint.root_hash) {
+        return Err(mismatch);
+    }
+    Ok(tree)
+}
+
+impl Bundle {
+    /// Packs the whole log (oldest first, from sequence number 1) and signs it.
+    /// Fails if the log's own chain is already broken, or if `checkpoint` is
+    /// not over a prefix of it.
+    pub fn create(
+        records: Vec<AuditEntry>,
+        checkpoint: Option<SignedCheckpoint>,
+        origin: &str,
+        created_ms: u64,
+        key: &DeviceKey,
+    ) -> Result<Bundle, BundleError> {
+        let head = verify_chain(&records).map_err(BundleError::Chain)?;
+        let proofs = match &checkpoint {
+            Some(checkpoint) => checkpoint_tree(&records, checkpoint)?.inclusion_proofs(),
+            None => Vec::new(),
+        };
+        let manifest = BundleManifest {
+            format: BUNDLE_FORMAT.into(),
+            origin: origin.into(),
+            created_ms,
+            record_count: records.len() as u64,
+            records_sha256: records_digest(&records)?,
+            chain_head: head.hash,
+            checkpoint,
+        };
+        let message = manifest.signing_message().map_err(|e| BundleError::Canonicalization(e.to_string()))?;
+        let signature = hex::encode(key.sign(&message).to_bytes());
+        Ok(Bundle { manifest, records, proofs, signer_pubkey: key.public_key_hex(), signature })
+    }
+
+    /// Parses the hex `signer_pubkey` of the bundle. As with
+    /// `verify::embedded_signer`, trusting it only proves integrity.
+    pub fn embedded_signer(&self) -> Result<VerifyingKey, BundleError> {
+        hex::decode(&self.signer_pubkey)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .and_then(|b: [u8; PUBLIC_KEY_LENGTH]| VerifyingKey::from_bytes(&b).ok())
+            .ok_or(BundleError::MalformedSignerKey)
+    }
+
+    /// Checks everything the bundle itself vouches for: the manifest
+    /// signature, that the records are exactly those committed to, that they
+    /// chain, and that the checkpoint (if any) is signed by `key` over them
+    /// with a valid inclusion proof for each record it covers.
+    /// The attestations inside still need `verify_attestation` each.
+    pub fn verify(&self, key: &VerifyingKey) -> Result<(), BundleError> {
+        let manifest = &self.manifest;
+        if manifest.format != BUNDLE_FORMAT {
+            return Err(BundleError::UnsupportedFormat(manifest.format.clone()));
+        }
+        let expected = hex::encode(key.to_bytes());
+        if !self.signer_pubkey.eq_ignore_ascii_case(&expected) {
+            return Err(BundleError::SignerMismatch { expected, found: self.signer_pubkey.clone() });
+        }
+        let sig_bytes: [u8; SIGNATURE_LENGTH] = hex::decode(&self.signature)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or(BundleError::MalformedSignature)?;
+        let message = manifest.signing_message().map_err(|e| BundleError::Canonicalization(e.to_string()))?;
+        key.verify_strict(&message, &Signature::from_bytes(&sig_bytes))
+            .map_err(|_| BundleError::BadSignature)?;
+
+        if records_digest(&self.records)? != manifest.records_sha256 {
+            return Err(BundleError::DigestMismatch);
+        }
+        let records = self.records.len() as u64;
+        if records != manifest.record_count {
+            return Err(BundleError::CountMismatch { manifest: manifest.record_count, records });
+        }
+        let head = verify_chain(&self.records).map_err(BundleError::Chain)?;
+        if head.hash != manifest.chain_head {
+            return Err(BundleError::HeadMismatch);
+        }
+
+        if let Some(checkpoint) = &manifest.checkpoint {
+            verify::verify_checkpoint(checkpoint, key).map_err(BundleError::Checkpoint)?;
+            let tree_size = checkpoint_tree(&self.records, checkpoint)?.size();
+            if self.proofs.len() as u64 != tree_size {
+                return Err(BundleError::ProofCount { tree_size, proofs: self.proofs.len() as u64 });
+            }
+            for (i, (proof, entry)) in self.proofs.iter().zip(&self.records).enumerate() {
+                if proof.leaf_index != i as u64 || proof.tree_size != tree_size {
+                    return Err(BundleError::MisplacedProof { seq: entry.seq });
+                }
+                proof
+                    .verify(&entry.attestation, &checkpoint.checkpoint.root_hash)
+                    .map_err(|why| BundleError::Proof { seq: entry.seq, why })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/* ===================== BROWSER DOWNLOAD ===================== */
+
+#[cfg(target_arch = "wasm32")]
+pub use browser::download;
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use wasm_bindgen::{JsCast, JsValue};
+    use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+
+    fn js_error(e: JsValue) -> String {
+        e.as_string().unwrap_or_else(|| format!("{:?}", e))
+    }
+
+    /// Saves `contents` as `filename` through a temporary object URL.
+    pub fn download(filename: &str, mime: &str, contents: &str) -> Result<(), String> {
+        let document = web_sys::window().and_then(|w| w.document()).ok_or("no document")?;
+        let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+        let options = BlobPropertyBag::new();
+        options.set_type(mime);
+        let blob = Blob::new_with_str_sequence_and_options(&parts, &options).map_err(js_error)?;
+        let url = Url::create_object_url_with_blob(&blob).map_err(js_error)?;
+        let anchor: HtmlAnchorElement = document.create_element("a").map_err(js_error)?.unchecked_into();
+        anchor.set_href(&url);
+        anchor.set_download(filename);
+        anchor.click();
+        // Some browsers start reading the blob only after the click handler returns.
+        gloo_timers::callback::Timeout::new(1_000, move || {
+            let _ = Url::revoke_object_url(&url);
+        })
+        .forget();
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+/* ===================== HOLD TIMING ===================== */
+
+/// Shortest hold accepted as deliberate human intent, in milliseconds.
+pub const DEFAULT_MIN_HOLD_MS: u64 = 1500;
+
+/// Press and release instants of a hold, in Unix epoch milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HoldTiming {
+    pub started_ms: u64,
+    pub ended_ms: u64,
+}
+
+/// Why a hold ended without completing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HoldError {
+    /// Released before the configured minimum.
+    TooShort { held_ms: u64, min_ms: u64 },
+    /// Released after dragging off the control.
+    ReleasedOutside,
+    /// The platform cancelled the pointer (scroll takeover, call, lost capture).
+    Interrupted,
+}
+
+impl std::fmt::Display for HoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HoldError::TooShort { held_ms, min_ms } => {
+                write!(f, "hold of {} ms is shorter than {} ms", held_ms, min_ms)
+            }
+            HoldError::ReleasedOutside => write!(f, "released outside the control"),
+            HoldError::Interrupted => write!(f, "hold interrupted"),
+        }
+    }
+}
+
+impl std::error::Error for HoldError {}
+
+impl HoldTiming {
+    /// Builds timing from two high-resolution epoch readings (see `now_epoch_ms`).
+    /// Both ends are rounded first so `duration_ms` always equals `ended_ms - started_ms`.
+    pub fn from_epoch_ms(pressed: f64, released: f64) -> Self {
+        let started_ms = pressed.round().max(0.0) as u64;
+        let ended_ms = (released.round().max(0.0) as u64).max(started_ms);
+        Self { started_ms, ended_ms }
+    }
+
+    pub fn duration_ms(&self) -> u64 {
+        self.ended_ms - self.started_ms
+    }
+
+    pub fn require_min(self, min_ms: u64) -> Result<Self, HoldError> {
+        let held_ms = self.duration_ms();
+        if held_ms < min_ms {
+            return Err(HoldError::TooShort { held_ms, min_ms });
+        }
+        Ok(self)
+    }
+}
+
+/// Monotonic wall clock: `performance.timeOrigin + performance.now()`.
+/// Unlike `Date.now()` it cannot jump while a hold is in progress.
+#[cfg(target_arch = "wasm32")]
+pub fn now_epoch_ms() -> f64 {
+    web_sys::window()
+        .and_then(|w| w.performance())
+        .map(|p| p.time_origin() + p.now())
+        .unwrap_or_else(js_sys::Date::now)
+}
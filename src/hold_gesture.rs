@@ -0,0 +1,207 @@
+use leptos::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, PointerEvent};
+
+use crate::hold::{self, HoldError, HoldTiming};
+
+/* ===================== HOLD GESTURE ===================== */
+
+/// Hold-to-act button with a progress bar. Progress is sampled once per
+/// animation frame from the press timestamp, so it tracks real time rather
+/// than an accumulation of timer ticks.
+///
+/// By default the gesture completes as soon as `duration_ms` elapses. With
+/// `complete_on_release` it completes on release instead, carrying the full
+/// press-to-release timing, and fails with `HoldError::TooShort` if let go early.
+#[component]
+pub fn HoldGesture(
+    /// Time the pointer must be held for the gesture to count, in milliseconds.
+    duration_ms: u64,
+    /// Fired with the measured timing when the hold succeeds.
+    #[prop(into)]
+    on_complete: Callback<HoldTiming>,
+    /// Fired when an engaged hold ends without completing.
+    #[prop(optional, into)]
+    on_cancel: Option<Callback<HoldError>>,
+    /// Consulted once the primary pointer is claimed; returning `false` ignores the press.
+    #[prop(optional, into)]
+    on_start: Option<Callback<(), bool>>,
+    /// Fired once when progress reaches 100% in `complete_on_release` mode.
+    #[prop(optional, into)]
+    on_armed: Option<Callback<()>>,
+    #[prop(optional)]
+    complete_on_release: bool,
+    #[prop(optional, into)]
+    disabled: MaybeSignal<bool>,
+    /// Classes for the button.
+    #[prop(default = "action-btn hold")]
+    class: &'static str,
+    /// Classes for the progress bar.
+    #[prop(default = "progress-bar")]
+    bar_class: &'static str,
+    children: ChildrenFn,
+) -> impl IntoView {
+    let gesture = Gesture {
+        holding: create_rw_signal(false),
+        progress: create_rw_signal(0.0),
+        pressed_at: store_value(0.0),
+        pointer: store_value(None),
+        duration_ms,
+        complete_on_release,
+        on_complete,
+        on_cancel,
+        on_armed,
+    };
+    // Stop the frame loop if the control unmounts mid-hold.
+    on_cleanup(move || gesture.holding.set_untracked(false));
+
+    view! {
+        <div class="hold-container">
+            <button class=class
+                disabled={move || disabled.get()}
+                on:pointerdown={move |ev| {
+                    // Claim the pointer first: `on_start` may update the UI, which
+                    // must not happen for a press that cannot start a hold.
+                    if gesture.holding.get_untracked() || !capture_pointer(&ev, gesture.pointer) {
+                        return;
+                    }
+                    if on_start.is_none_or(|cb| cb.call(())) {
+                        gesture.begin();
+                    } else {
+                        drop_pointer(&ev, gesture.pointer);
+                    }
+                }}
+                on:pointerup={move |ev| match release_pointer(&ev, gesture.pointer) {
+                    Some(true) => gesture.release(),
+                    Some(false) => gesture.cancel(HoldError::ReleasedOutside),
+                    None => {}
+                }}
+                on:pointercancel={move |ev| if release_pointer(&ev, gesture.pointer).is_some() { gesture.cancel(HoldError::Interrupted) }}
+                on:lostpointercapture={move |ev| if release_pointer(&ev, gesture.pointer).is_some() { gesture.cancel(HoldError::Interrupted) }}
+                on:contextmenu={move |ev| ev.prevent_default()}
+            >
+                {children()}
+            </button>
+            <div class=bar_class style:width={move || format!("{:.1}%", gesture.progress.get() * 100.0)}></div>
+        </div>
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Gesture {
+    holding: RwSignal<bool>,
+    progress: RwSignal<f64>,
+    pressed_at: StoredValue<f64>,
+    pointer: StoredValue<Option<i32>>,
+    duration_ms: u64,
+    complete_on_release: bool,
+    on_complete: Callback<HoldTiming>,
+    on_cancel: Option<Callback<HoldError>>,
+    on_armed: Option<Callback<()>>,
+}
+
+impl Gesture {
+    fn begin(self) {
+        self.pressed_at.set_value(hold::now_epoch_ms());
+        self.progress.set(0.0);
+        self.holding.set(true);
+        request_animation_frame(move || self.frame());
+    }
+
+    fn frame(self) {
+        if !self.holding.get_untracked() {
+            return;
+        }
+        let elapsed = hold::now_epoch_ms() - self.pressed_at.get_value();
+        let progress = (elapsed / self.duration_ms.max(1) as f64).clamp(0.0, 1.0);
+        let was_full = self.progress.get_untracked() >= 1.0;
+        self.progress.set(progress);
+
+        if progress >= 1.0 && !was_full {
+            if self.complete_on_release {
+                if let Some(cb) = self.on_armed { cb.call(()) }
+            } else {
+                self.pointer.set_value(None);
+                self.finish(Ok(()));
+                return;
+            }
+        }
+        request_animation_frame(move || self.frame());
+    }
+
+    fn release(self) {
+        if !self.holding.get_untracked() {
+            return;
+        }
+        if self.complete_on_release {
+            self.finish(Ok(()));
+        } else {
+            let timing = self.timing();
+            self.finish(Err(HoldError::TooShort { held_ms: timing.duration_ms(), min_ms: self.duration_ms }));
+        }
+    }
+
+    fn cancel(self, why: HoldError) {
+        if self.holding.get_untracked() {
+            self.finish(Err(why));
+        }
+    }
+
+    fn finish(self, outcome: Result<(), HoldError>) {
+        let timing = self.timing();
+        self.holding.set(false);
+        self.progress.set(0.0);
+        match outcome.and_then(|_| timing.require_min(self.duration_ms)) {
+            Ok(timing) => self.on_complete.call(timing),
+            Err(why) => {
+                if let Some(cb) = self.on_cancel { cb.call(why) }
+            }
+        }
+    }
+
+    fn timing(self) -> HoldTiming {
+        HoldTiming::from_epoch_ms(self.pressed_at.get_value(), hold::now_epoch_ms())
+    }
+}
+
+/// Claims the primary pointer for the button and captures it, so the release
+/// is delivered here even when it happens off the button. Secondary touches and
+/// non-primary mouse buttons are ignored.
+fn capture_pointer(ev: &PointerEvent, active: StoredValue<Option<i32>>) -> bool {
+    if !ev.is_primary() || ev.button() > 0 || active.get_value().is_some() {
+        return false;
+    }
+    ev.prevent_default();
+    if let Some(target) = ev.current_target().and_then(|t| t.dyn_into::<Element>().ok()) {
+        let _ = target.set_pointer_capture(ev.pointer_id());
+    }
+    active.set_value(Some(ev.pointer_id()));
+    true
+}
+
+/// Gives back a pointer claimed by `capture_pointer` without starting a hold.
+fn drop_pointer(ev: &PointerEvent, active: StoredValue<Option<i32>>) {
+    active.set_value(None);
+    if let Some(target) = ev.current_target().and_then(|t| t.dyn_into::<Element>().ok()) {
+        let _ = target.release_pointer_capture(ev.pointer_id());
+    }
+}
+
+/// Ends the hold owned by this pointer. Returns `None` for unrelated pointers,
+/// otherwise whether the pointer was still over the button when it let go.
+fn release_pointer(ev: &PointerEvent, active: StoredValue<Option<i32>>) -> Option<bool> {
+    if active.get_value() != Some(ev.pointer_id()) {
+        return None;
+    }
+    active.set_value(None);
+    let inside = ev
+        .current_target()
+        .and_then(|t| t.dyn_into::<Element>().ok())
+        .map(|el| {
+            let rect = el.get_bounding_client_rect();
+            let (x, y) = (ev.client_x() as f64, ev.client_y() as f64);
+            x >= rect.left() && x <= rect.right() && y >= rect.top() && y <= rect.bottom()
+        })
+        .unwrap_or(false);
+    Some(inside)
+}
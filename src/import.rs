@@ -0,0 +1,196 @@
+/* ===================== ATTESTATION FILE IMPORT ===================== */
+
+// Attestation files handed to an auditor come back in one of the shapes the
+// vault writes: a single record, a JSON array, JSON Lines, or a signed bundle
+// from `export`. `check_file` verifies each record on its own (signature and
+// policy) and against the rest of the file: a nonce may appear only once, and
+// the records of a bundle must link up as a hash chain. Every problem is
+// reported against the record it was found at, not just the first.
+
+use std::collections::HashMap;
+
+use ed25519_dalek::VerifyingKey;
+
+use crate::attestation::IntentAttestation;
+use crate::audit::{AuditEntry, ChainBreak, ChainHead};
+use crate::export::{Bundle, BundleError};
+use crate::verify::{self, Policy, Verified, VerifyError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportError {
+    /// The file holds no records.
+    Empty,
+    /// The file is not JSON, JSON Lines or a bundle of attestations.
+    Parse(String),
+}
+
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportError::Empty => write!(f, "file holds no attestations"),
+            ImportError::Parse(why) => write!(f, "file unreadable: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// The contents of one imported file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttestationFile {
+    Records(Vec<IntentAttestation>),
+    Bundle(Box<Bundle>),
+}
+
+impl AttestationFile {
+    /// Reads a bundle, one attestation, an array of them or JSON Lines.
+    /// Attestations in older schema versions are upgraded on the way in.
+    pub fn parse(raw: &str) -> Result<Self, ImportError> {
+        let parse = |e: serde_json::Error| ImportError::Parse(e.to_string());
+        let values = match serde_json::from_str::<serde_json::Value>(raw) {
+            Ok(value) if value.get("manifest").is_some() => {
+                return serde_json::from_value(value).map(|b| AttestationFile::Bundle(Box::new(b))).map_err(parse);
+            }
+            Ok(serde_json::Value::Array(items)) => items,
+            Ok(single) => vec![single],
+            // Not one JSON document; try JSON Lines.
+            Err(_) => raw
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(n, line)| serde_json::from_str(line).map_err(|e| ImportError::Parse(format!("line {}: {}", n + 1, e))))
+                .collect::<Result<_, _>>()?,
+        };
+        if values.is_empty() {
+            return Err(ImportError::Empty);
+        }
+        let records = values.into_iter().map(serde_json::from_value).collect::<Result<_, _>>().map_err(parse)?;
+        Ok(AttestationFile::Records(records))
+    }
+
+    pub fn attestations(&self) -> Vec<&IntentAttestation> {
+        match self {
+            AttestationFile::Records(records) => records.iter().collect(),
+            AttestationFile::Bundle(bundle) => bundle.records.iter().map(|entry| &entry.attestation).collect(),
+        }
+    }
+}
+
+/// Why one record of a file does not check out.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordIssue {
+    /// The record fails verification on its own.
+    Rejected(VerifyError),
+    /// The record reuses the nonce of the record at index `first`.
+    DuplicateNonce { first: usize },
+    /// The bundle record does not link to the one before it.
+    Unlinked(ChainBreak),
+    /// The bundle record was sealed by a key other than the bundle's signer.
+    ForeignSigner { signer: String },
+}
+
+impl std::fmt::Display for RecordIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordIssue::Rejected(why) => write!(f, "{}", why),
+            RecordIssue::DuplicateNonce { first } => write!(f, "nonce already used by record {}", first),
+            RecordIssue::Unlinked(brk) => write!(f, "{}", brk),
+            RecordIssue::ForeignSigner { signer } => write!(f, "sealed by {}, not the bundle signer", signer),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordReport {
+    /// Position in the file, from 0.
+    pub index: usize,
+    /// Audit log sequence number, for bundle records.
+    pub seq: Option<u64>,
+    pub attestation: IntentAttestation,
+    /// The signature and policy verdict, present when those checks passed.
+    pub verified: Option<Verified>,
+    pub issues: Vec<RecordIssue>,
+}
+
+impl RecordReport {
+    pub fn passed(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileReport {
+    /// The bundle-level verdict (manifest signature, digest, chain head,
+    /// checkpoint); `None` for plain attestation files.
+    pub bundle: Option<Result<(), BundleError>>,
+    /// Whether signatures were checked against a key the caller trusts. When
+    /// false they were checked against the keys the file names itself, which
+    /// shows the file is intact but not who wrote it.
+    pub authenticated: bool,
+    pub records: Vec<RecordReport>,
+}
+
+impl FileReport {
+    pub fn passed(&self) -> bool {
+        !matches!(self.bundle, Some(Err(_))) && self.records.iter().all(RecordReport::passed)
+    }
+
+    pub fn failed_records(&self) -> usize {
+        self.records.iter().filter(|r| !r.passed()).count()
+    }
+}
+
+/// Checks every record of `file` against `trusted`, or against each record's
+/// (and the bundle's) embedded signer when no key is trusted. Bundle records
+/// must all be sealed by the bundle signer either way.
+pub fn check_file(file: &AttestationFile, trusted: Option<&VerifyingKey>, policy: &Policy) -> FileReport {
+    let bundle = match file {
+        AttestationFile::Records(_) => None,
+        AttestationFile::Bundle(bundle) => Some(match trusted {
+            Some(key) => bundle.verify(key),
+            None => bundle.embedded_signer().and_then(|key| bundle.verify(&key)),
+        }),
+    };
+    let (entries, bundle_signer): (Option<&[AuditEntry]>, _) = match file {
+        AttestationFile::Records(_) => (None, None),
+        AttestationFile::Bundle(bundle) => (Some(&bundle.records), Some(bundle.signer_pubkey.as_str())),
+    };
+
+    let mut nonces = HashMap::new();
+    let mut head = ChainHead::genesis();
+    let records = file
+        .attestations()
+        .into_iter()
+        .enumerate()
+        .map(|(index, attestation)| {
+            let mut issues = Vec::new();
+            let key = match trusted {
+                Some(key) => Ok(*key),
+                None => verify::embedded_signer(attestation),
+            };
+            let verified = key
+                .and_then(|key| verify::verify_attestation(attestation, &key, policy))
+                .map_err(|e| issues.push(RecordIssue::Rejected(e)))
+                .ok();
+            if let Some(&first) = nonces.get(&attestation.body.nonce) {
+                issues.push(RecordIssue::DuplicateNonce { first });
+            } else {
+                nonces.insert(attestation.body.nonce.clone(), index);
+            }
+            let signer = &attestation.body.signer_pubkey;
+            if bundle_signer.is_some_and(|expected| !expected.eq_ignore_ascii_case(signer)) {
+                issues.push(RecordIssue::ForeignSigner { signer: signer.clone() });
+            }
+            let entry = entries.map(|entries| &entries[index]);
+            if let Some(entry) = entry {
+                // Pick the chain up again after a break, so each bad link is reported once.
+                head = head.link(entry).unwrap_or_else(|brk| {
+                    issues.push(RecordIssue::Unlinked(brk));
+                    ChainHead { len: entry.seq, hash: entry.hash().unwrap_or_default() }
+                });
+            }
+            RecordReport { index, seq: entry.map(|e| e.seq), attestation: attestation.clone(), verified, issues }
+        })
+        .collect();
+    FileReport { bundle, authenticated: trusted.is_some(), records }
+}
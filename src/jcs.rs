@@ -0,0 +1,172 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/* ===================== RFC 8785 JSON CANONICALIZATION ===================== */
+
+/// Largest integer magnitude an IEEE-754 double represents exactly (2^53).
+const MAX_SAFE_INTEGER: u64 = 9_007_199_254_740_992;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JcsError {
+    /// An integer outside ±2^53 cannot round-trip through an I-JSON number.
+    IntegerOutOfRange(String),
+    /// The value could not be converted into a JSON tree.
+    Serialize(String),
+}
+
+impl std::fmt::Display for JcsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JcsError::IntegerOutOfRange(n) => write!(f, "integer {} exceeds IEEE-754 precision", n),
+            JcsError::Serialize(why) => write!(f, "cannot serialize value: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for JcsError {}
+
+/// Serializes `value` and returns its RFC 8785 canonical UTF-8 bytes.
+pub fn to_canonical_vec<T: Serialize>(value: &T) -> Result<Vec<u8>, JcsError> {
+    let tree = serde_json::to_value(value).map_err(|e| JcsError::Serialize(e.to_string()))?;
+    canonicalize(&tree).map(String::into_bytes)
+}
+
+/// Renders a JSON tree in RFC 8785 canonical form.
+pub fn canonicalize(value: &Value) -> Result<String, JcsError> {
+    let mut out = String::new();
+    write_value(&mut out, value)?;
+    Ok(out)
+}
+
+fn write_value(out: &mut String, value: &Value) -> Result<(), JcsError> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => write_number(out, n)?,
+        Value::String(s) => write_string(out, s),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 { out.push(','); }
+                write_value(out, item)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            // Members are ordered by the UTF-16 code units of their names (RFC 8785 §3.2.3).
+            let mut members: Vec<(&String, &Value)> = map.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (key, item)) in members.into_iter().enumerate() {
+                if i > 0 { out.push(','); }
+                write_string(out, key);
+                out.push(':');
+                write_value(out, item)?;
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0C}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_number(out: &mut String, n: &serde_json::Number) -> Result<(), JcsError> {
+    if let Some(u) = n.as_u64() {
+        if u > MAX_SAFE_INTEGER {
+            return Err(JcsError::IntegerOutOfRange(u.to_string()));
+        }
+        out.push_str(&u.to_string());
+    } else if let Some(i) = n.as_i64() {
+        if i.unsigned_abs() > MAX_SAFE_INTEGER {
+            return Err(JcsError::IntegerOutOfRange(i.to_string()));
+        }
+        out.push_str(&i.to_string());
+    } else if let Some(f) = n.as_f64() {
+        out.push_str(&format_double(f));
+    }
+    Ok(())
+}
+
+/// ECMAScript `Number.prototype.toString` for a finite double (ECMA-262 §6.1.6.1.20).
+pub fn format_double(value: f64) -> String {
+    if value == 0.0 {
+        return "0".into(); // Covers -0 as well.
+    }
+
+    // `{:e}` yields the shortest round-tripping digits, e.g. "-1.2345e-7".
+    let sci = format!("{:e}", value);
+    let (mantissa, exp) = sci.split_once('e').expect("LowerExp always emits an exponent");
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(m) => ("-", m),
+        None => ("", mantissa),
+    };
+    let mut digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    if let Some(even) = even_tie_digits(value.abs(), &digits, exp) {
+        digits = even;
+    }
+    let k = digits.len() as i32;
+    // Decimal point position relative to the digit string: value = 0.digits × 10^n.
+    let n = exp.parse::<i32>().expect("LowerExp exponent is an integer") + 1;
+
+    let body = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat((-n) as usize), digits)
+    } else {
+        let exp_sign = if n - 1 < 0 { "-" } else { "+" };
+        let exp_abs = (n - 1).abs();
+        if k == 1 {
+            format!("{}e{}{}", digits, exp_sign, exp_abs)
+        } else {
+            format!("{}.{}e{}{}", &digits[..1], &digits[1..], exp_sign, exp_abs)
+        }
+    };
+    format!("{}{}", sign, body)
+}
+
+/// ECMAScript breaks ties between two equally short, equally close digit strings
+/// towards the even one; Rust's shortest formatter rounds half up. Returns the
+/// even candidate when `value` sits exactly halfway and that candidate round-trips.
+fn even_tie_digits(value: f64, shortest: &str, exp: &str) -> Option<String> {
+    // 800 fractional digits exceed the longest exact expansion of any f64.
+    let exact = format!("{:.800e}", value);
+    let (exact_mantissa, exact_exp) = exact.split_once('e')?;
+    if exact_exp != exp {
+        return None;
+    }
+    let exact_digits: String = exact_mantissa.chars().filter(|c| *c != '.').collect();
+    let exact_digits = exact_digits.trim_end_matches('0');
+
+    let k = shortest.len();
+    if exact_digits.len() != k + 1 || !exact_digits.ends_with('5') {
+        return None;
+    }
+    let truncated = &exact_digits[..k];
+    let last = truncated.as_bytes()[k - 1] - b'0';
+    if truncated == shortest || !last.is_multiple_of(2) {
+        return None;
+    }
+
+    let scale = exp.parse::<i32>().ok()? - (k as i32 - 1);
+    let candidate = format!("{}e{}", truncated, scale);
+    (candidate.parse::<f64>().ok()? == value).then(|| truncated.to_string())
+}
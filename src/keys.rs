@@ -0,0 +1,194 @@
+use base64::{engine::general_purpose::STANDARD as B64, Engine};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey, SECRET_KEY_LENGTH};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+/* ===================== DEVICE KEY MANAGER ===================== */
+
+/// localStorage slot holding the sealed device key.
+pub const STORAGE_KEY: &str = "vext.device_key";
+
+/// PBKDF2-HMAC-SHA256 work factor for newly sealed keys (OWASP 2023 guidance).
+pub const KDF_ITERATIONS: u32 = 600_000;
+
+/// Fewest iterations `SealedKey::open` accepts. The count is stored next to
+/// the ciphertext, so without a floor an edited record would make the
+/// passphrase cheap to brute-force once re-sealed.
+pub const MIN_KDF_ITERATIONS: u32 = 600_000;
+
+const SEALED_KEY_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyError {
+    /// The passphrase did not decrypt the sealed key.
+    WrongPassphrase,
+    /// The stored record is malformed or does not match its public key.
+    Corrupted(String),
+    /// Browser storage is unavailable or rejected the write.
+    Storage(String),
+}
+
+impl std::fmt::Display for KeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyError::WrongPassphrase => write!(f, "wrong passphrase"),
+            KeyError::Corrupted(why) => write!(f, "sealed key corrupted: {}", why),
+            KeyError::Storage(why) => write!(f, "key storage unavailable: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for KeyError {}
+
+/// Per-device Ed25519 signing key. The inner `SigningKey` zeroizes itself on drop.
+pub struct DeviceKey {
+    signing_key: SigningKey,
+}
+
+impl DeviceKey {
+    /// Generates a fresh key from the platform CSPRNG.
+    pub fn generate() -> Self {
+        Self { signing_key: SigningKey::generate(&mut OsRng) }
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+
+    /// Hex-encoded public key, as carried in `IntentAttestation::signer_pubkey`.
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.verifying_key().to_bytes())
+    }
+
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        self.signing_key.sign(message)
+    }
+
+    /// Encrypts the secret key under a passphrase-derived key.
+    pub fn seal(&self, passphrase: &str) -> SealedKey {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let wrap_key = derive_wrap_key(passphrase, &salt, KDF_ITERATIONS);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(wrap_key.as_slice()));
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let secret = Zeroizing::new(self.signing_key.to_bytes());
+        let ciphertext = cipher
+            .encrypt(&nonce, secret.as_slice())
+            .expect("ChaCha20Poly1305 encryption of 32 bytes cannot fail");
+
+        SealedKey {
+            version: SEALED_KEY_VERSION,
+            kdf_iterations: KDF_ITERATIONS,
+            salt: B64.encode(salt),
+            nonce: B64.encode(nonce),
+            ciphertext: B64.encode(ciphertext),
+            public_key: self.public_key_hex(),
+        }
+    }
+}
+
+/// At-rest form of a `DeviceKey`: PBKDF2-HMAC-SHA256 wrap key + ChaCha20-Poly1305.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SealedKey {
+    pub version: u8,
+    pub kdf_iterations: u32,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+    pub public_key: String,
+}
+
+impl SealedKey {
+    /// Decrypts the secret key and checks it against the recorded public key.
+    pub fn open(&self, passphrase: &str) -> Result<DeviceKey, KeyError> {
+        if self.version != SEALED_KEY_VERSION {
+            return Err(KeyError::Corrupted(format!("unsupported version {}", self.version)));
+        }
+        if self.kdf_iterations < MIN_KDF_ITERATIONS {
+            return Err(KeyError::Corrupted(format!("{} KDF iterations is below the minimum", self.kdf_iterations)));
+        }
+        let salt = decode_field("salt", &self.salt)?;
+        let nonce = decode_field("nonce", &self.nonce)?;
+        let ciphertext = decode_field("ciphertext", &self.ciphertext)?;
+        if nonce.len() != 12 {
+            return Err(KeyError::Corrupted("nonce length".into()));
+        }
+
+        let wrap_key = derive_wrap_key(passphrase, &salt, self.kdf_iterations);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(wrap_key.as_slice()));
+        let secret = Zeroizing::new(
+            cipher
+                .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+                .map_err(|_| KeyError::WrongPassphrase)?,
+        );
+        let secret: &[u8; SECRET_KEY_LENGTH] = secret
+            .as_slice()
+            .try_into()
+            .map_err(|_| KeyError::Corrupted("secret length".into()))?;
+
+        let key = DeviceKey { signing_key: SigningKey::from_bytes(secret) };
+        if key.public_key_hex() != self.public_key {
+            return Err(KeyError::Corrupted("public key mismatch".into()));
+        }
+        Ok(key)
+    }
+}
+
+fn derive_wrap_key(passphrase: &str, salt: &[u8], iterations: u32) -> Zeroizing<[u8; 32]> {
+    let mut out = Zeroizing::new([0u8; 32]);
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, out.as_mut_slice());
+    out
+}
+
+fn decode_field(name: &str, value: &str) -> Result<Vec<u8>, KeyError> {
+    B64.decode(value).map_err(|_| KeyError::Corrupted(format!("{} is not base64", name)))
+}
+
+/* ===================== BROWSER PERSISTENCE ===================== */
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Result<web_sys::Storage, KeyError> {
+    web_sys::window()
+        .and_then(|w| w.local_storage().ok().flatten())
+        .ok_or_else(|| KeyError::Storage("localStorage not available".into()))
+}
+
+/// True when this browser already holds a sealed device key.
+#[cfg(target_arch = "wasm32")]
+pub fn has_stored_key() -> bool {
+    local_storage()
+        .ok()
+        .and_then(|s| s.get_item(STORAGE_KEY).ok().flatten())
+        .is_some()
+}
+
+/// Unseals the stored device key, or generates and persists one on first run.
+#[cfg(target_arch = "wasm32")]
+pub fn load_or_create(passphrase: &str) -> Result<DeviceKey, KeyError> {
+    let storage = local_storage()?;
+    let stored = storage
+        .get_item(STORAGE_KEY)
+        .map_err(|_| KeyError::Storage("read failed".into()))?;
+
+    match stored {
+        Some(raw) => {
+            let sealed: SealedKey = serde_json::from_str(&raw)
+                .map_err(|e| KeyError::Corrupted(e.to_string()))?;
+            sealed.open(passphrase)
+        }
+        None => {
+            let key = DeviceKey::generate();
+            let sealed = serde_json::to_string(&key.seal(passphrase))
+                .map_err(|e| KeyError::Corrupted(e.to_string()))?;
+            storage
+                .set_item(STORAGE_KEY, &sealed)
+                .map_err(|_| KeyError::Storage("write failed".into()))?;
+            Ok(key)
+        }
+    }
+}
@@ -0,0 +1,340 @@
+/* ===================== MERKLE BATCHING & CHECKPOINTS ===================== */
+
+// Attestations are committed to in batches, transparency-log style (RFC 9162):
+// every sealed attestation becomes a leaf of an append-only Merkle tree, the
+// device key periodically signs a checkpoint of the tree's size and root, and
+// an inclusion proof shows one attestation is under a checkpointed root
+// without revealing the others.
+//
+// Leaves are the SHA-256 of the attestation's canonical (RFC 8785) JSON.
+// Leaf and interior hashes are domain-separated with 0x00 / 0x01 prefixes as in
+// RFC 9162, so an interior node can never pass for a leaf.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::attestation::IntentAttestation;
+use crate::audit::AuditEntry;
+use crate::jcs::{self, JcsError};
+use crate::keys::DeviceKey;
+
+/// Domain separator for checkpoint signatures, so a checkpoint signature can
+/// never be replayed as an attestation signature or vice versa.
+pub const CHECKPOINT_CONTEXT: &str = "VEXT-CHECKPOINT-V1";
+
+/// Where the browser keeps the signed checkpoints, oldest first.
+pub const CHECKPOINTS_STORAGE_KEY: &str = "vext.checkpoints";
+
+/// Minimum spacing of checkpoints over a growing tree, in milliseconds.
+pub const DEFAULT_CHECKPOINT_INTERVAL_MS: u64 = 600_000;
+
+pub type Hash = [u8; 32];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MerkleError {
+    /// `leaf_index` is not below `tree_size`.
+    IndexOutOfRange { index: u64, size: u64 },
+    /// A proof hash or root is not 32 hex-encoded bytes.
+    MalformedHash(String),
+    /// The proof has the wrong number of hashes for its position.
+    WrongPathLength,
+    /// The proof does not lead to the expected root.
+    RootMismatch,
+}
+
+impl std::fmt::Display for MerkleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MerkleError::IndexOutOfRange { index, size } => write!(f, "leaf {} outside tree of size {}", index, size),
+            MerkleError::MalformedHash(h) => write!(f, "{:?} is not a hex SHA-256 hash", h),
+            MerkleError::WrongPathLength => write!(f, "inclusion proof has the wrong length"),
+            MerkleError::RootMismatch => write!(f, "inclusion proof does not lead to the root"),
+        }
+    }
+}
+
+impl std::error::Error for MerkleError {}
+
+/// SHA-256 of the attestation's canonical JSON; the leaf input for the tree.
+pub fn attestation_hash(attestation: &IntentAttestation) -> Result<Hash, JcsError> {
+    Ok(Sha256::digest(jcs::to_canonical_vec(attestation)?).into())
+}
+
+pub fn leaf_hash(data: &[u8]) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+pub fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn parse_hash(hex_hash: &str) -> Result<Hash, MerkleError> {
+    hex::decode(hex_hash)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| MerkleError::MalformedHash(hex_hash.to_string()))
+}
+
+/// Largest power of two strictly below `n` (n > 1).
+fn split_point(n: usize) -> usize {
+    let mut k = 1;
+    while k << 1 < n {
+        k <<= 1;
+    }
+    k
+}
+
+/// Merkle tree hash of a run of leaf hashes.
+fn subtree_root(leaves: &[Hash]) -> Hash {
+    match leaves.len() {
+        0 => Sha256::digest([]).into(),
+        1 => leaves[0],
+        n => {
+            let k = split_point(n);
+            node_hash(&subtree_root(&leaves[..k]), &subtree_root(&leaves[k..]))
+        }
+    }
+}
+
+fn audit_path(index: usize, leaves: &[Hash]) -> Vec<Hash> {
+    if leaves.len() <= 1 {
+        return Vec::new();
+    }
+    let k = split_point(leaves.len());
+    let (mut path, sibling) = if index < k {
+        (audit_path(index, &leaves[..k]), subtree_root(&leaves[k..]))
+    } else {
+        (audit_path(index - k, &leaves[k..]), subtree_root(&leaves[..k]))
+    };
+    path.push(sibling);
+    path
+}
+
+/// Fills `paths` (one per leaf) with every audit path at once and returns the
+/// subtree root. Each interior hash is computed once, so all proofs cost
+/// O(n log n) rather than O(n) each.
+fn audit_paths(leaves: &[Hash], paths: &mut [Vec<Hash>]) -> Hash {
+    if leaves.len() <= 1 {
+        return subtree_root(leaves);
+    }
+    let k = split_point(leaves.len());
+    let (left_paths, right_paths) = paths.split_at_mut(k);
+    let left = audit_paths(&leaves[..k], left_paths);
+    let right = audit_paths(&leaves[k..], right_paths);
+    left_paths.iter_mut().for_each(|path| path.push(right));
+    right_paths.iter_mut().for_each(|path| path.push(left));
+    node_hash(&left, &right)
+}
+
+/// Append-only tree over attestation hashes; leaf `i` is the `i`-th attestation appended.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MerkleTree {
+    leaves: Vec<Hash>,
+}
+
+impl MerkleTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `attestation` and returns its leaf index.
+    pub fn push(&mut self, attestation: &IntentAttestation) -> Result<u64, JcsError> {
+        Ok(self.push_leaf(leaf_hash(&attestation_hash(attestation)?)))
+    }
+
+    /// Appends an already computed leaf hash and returns its index.
+    pub fn push_leaf(&mut self, leaf: Hash) -> u64 {
+        self.leaves.push(leaf);
+        self.leaves.len() as u64 - 1
+    }
+
+    /// Appends the audit log entries the tree does not cover yet, so leaf `i`
+    /// stays the entry with sequence number `i + 1`. Entries already covered
+    /// are skipped; at a gap the rest is left for a later call that supplies
+    /// the missing entries. Returns the tree size afterwards.
+    pub fn sync(&mut self, entries: &[AuditEntry]) -> Result<u64, JcsError> {
+        for entry in entries {
+            if entry.seq > self.size() + 1 {
+                break;
+            }
+            if entry.seq == self.size() + 1 {
+                self.push(&entry.attestation)?;
+            }
+        }
+        Ok(self.size())
+    }
+
+    pub fn size(&self) -> u64 {
+        self.leaves.len() as u64
+    }
+
+    pub fn root(&self) -> Hash {
+        subtree_root(&self.leaves)
+    }
+
+    /// Proof that leaf `index` is under the current root.
+    pub fn inclusion_proof(&self, index: u64) -> Result<InclusionProof, MerkleError> {
+        let size = self.size();
+        if index >= size {
+            return Err(MerkleError::IndexOutOfRange { index, size });
+        }
+        Ok(InclusionProof {
+            leaf_index: index,
+            tree_size: size,
+            audit_path: audit_path(index as usize, &self.leaves).iter().map(hex::encode).collect(),
+        })
+    }
+
+    /// Proofs for every leaf under the current root, in leaf order. Prefer this
+    /// over calling `inclusion_proof` per leaf when proving the whole tree.
+    pub fn inclusion_proofs(&self) -> Vec<InclusionProof> {
+        let mut paths = vec![Vec::new(); self.leaves.len()];
+        audit_paths(&self.leaves, &mut paths);
+        paths
+            .into_iter()
+            .enumerate()
+            .map(|(i, path)| InclusionProof {
+                leaf_index: i as u64,
+                tree_size: self.size(),
+                audit_path: path.iter().map(hex::encode).collect(),
+            })
+            .collect()
+    }
+
+    /// An unsigned checkpoint of the current tree.
+    pub fn checkpoint(&self, origin: &str, timestamp_ms: u64) -> Checkpoint {
+        Checkpoint {
+            origin: origin.into(),
+            tree_size: self.size(),
+            root_hash: hex::encode(self.root()),
+            timestamp_ms,
+        }
+    }
+}
+
+/// The sibling hashes from a leaf up to the root of a tree of `tree_size` leaves.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InclusionProof {
+    pub leaf_index: u64,
+    pub tree_size: u64,
+    /// Hex SHA-256 hashes, leaf level first.
+    pub audit_path: Vec<String>,
+}
+
+impl InclusionProof {
+    /// Checks that `attestation` is leaf `leaf_index` of the tree with `root_hash` (hex).
+    pub fn verify(&self, attestation: &IntentAttestation, root_hash: &str) -> Result<(), MerkleError> {
+        let leaf = attestation_hash(attestation).map_err(|e| MerkleError::MalformedHash(e.to_string()))?;
+        self.verify_leaf(&leaf_hash(&leaf), &parse_hash(root_hash)?)
+    }
+
+    /// RFC 9162 §2.1.3.2 inclusion verification.
+    pub fn verify_leaf(&self, leaf: &Hash, root: &Hash) -> Result<(), MerkleError> {
+        if self.leaf_index >= self.tree_size {
+            return Err(MerkleError::IndexOutOfRange { index: self.leaf_index, size: self.tree_size });
+        }
+        let (mut fnode, mut snode) = (self.leaf_index, self.tree_size - 1);
+        let mut hash = *leaf;
+        for sibling in &self.audit_path {
+            let sibling = parse_hash(sibling)?;
+            if snode == 0 {
+                return Err(MerkleError::WrongPathLength);
+            }
+            if fnode & 1 == 1 || fnode == snode {
+                hash = node_hash(&sibling, &hash);
+                while fnode & 1 == 0 && fnode != 0 {
+                    fnode >>= 1;
+                    snode >>= 1;
+                }
+            } else {
+                hash = node_hash(&hash, &sibling);
+            }
+            fnode >>= 1;
+            snode >>= 1;
+        }
+        if snode != 0 {
+            return Err(MerkleError::WrongPathLength);
+        }
+        if hash != *root {
+            return Err(MerkleError::RootMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// A statement of the tree's size and root at a point in time.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Checkpoint {
+    /// Which log this is, e.g. the vault's origin.
+    pub origin: String,
+    pub tree_size: u64,
+    /// Hex SHA-256 root of the first `tree_size` leaves.
+    pub root_hash: String,
+    pub timestamp_ms: u64,
+}
+
+impl Checkpoint {
+    /// Canonical bytes the device key signs, bound to `CHECKPOINT_CONTEXT`.
+    pub fn signing_message(&self) -> Result<Vec<u8>, JcsError> {
+        jcs::to_canonical_vec(&json!({ "context": CHECKPOINT_CONTEXT, "checkpoint": self }))
+    }
+
+    pub fn sign(self, key: &DeviceKey) -> Result<SignedCheckpoint, JcsError> {
+        let signature = hex::encode(key.sign(&self.signing_message()?).to_bytes());
+        Ok(SignedCheckpoint { checkpoint: self, signer_pubkey: key.public_key_hex(), signature })
+    }
+
+    /// Whether a new checkpoint is due over a tree of `tree_size` leaves:
+    /// the tree grew since `last` and at least `interval_ms` has passed.
+    pub fn due(last: Option<&Checkpoint>, tree_size: u64, now_ms: u64, interval_ms: u64) -> bool {
+        match last {
+            None => tree_size > 0,
+            Some(last) => tree_size > last.tree_size && now_ms.saturating_sub(last.timestamp_ms) >= interval_ms,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SignedCheckpoint {
+    pub checkpoint: Checkpoint,
+    /// Hex Ed25519 device key that signed.
+    pub signer_pubkey: String,
+    /// Hex Ed25519 signature over `Checkpoint::signing_message`.
+    pub signature: String,
+}
+
+/* ===================== BROWSER PERSISTENCE ===================== */
+
+#[cfg(target_arch = "wasm32")]
+pub use browser::{store_checkpoints, stored_checkpoints};
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use super::{SignedCheckpoint, CHECKPOINTS_STORAGE_KEY};
+
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window().and_then(|w| w.local_storage().ok().flatten())
+    }
+
+    /// Checkpoints signed in earlier sessions, oldest first.
+    pub fn stored_checkpoints() -> Vec<SignedCheckpoint> {
+        storage()
+            .and_then(|s| s.get_item(CHECKPOINTS_STORAGE_KEY).ok().flatten())
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn store_checkpoints(checkpoints: &[SignedCheckpoint]) {
+        if let (Some(s), Ok(raw)) = (storage(), serde_json::to_string(checkpoints)) {
+            let _ = s.set_item(CHECKPOINTS_STORAGE_KEY, &raw);
+        }
+    }
+}
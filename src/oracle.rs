@@ -0,0 +1,641 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use futures::future::{join_all, LocalBoxFuture};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::attestation::PriceQuote;
+use crate::jcs::{self, JcsError};
+
+/* ===================== PRICE ORACLE ===================== */
+
+// Spot prices come from several independent exchanges. Each adapter turns one
+// exchange's public ticker into a `Quote`; `OracleSet` fetches them together,
+// drops stale quotes and outliers, and reports the median. Transport is behind
+// `HttpGet` so the adapters run against a local mock server in tests and
+// against `fetch` in the browser.
+
+/// Quotes older than this are ignored, and a displayed price older than this is stale.
+pub const DEFAULT_MAX_QUOTE_AGE_MS: u64 = 60_000;
+/// Quotes further than this from the median, in basis points, are discarded.
+pub const DEFAULT_MAX_DEVIATION_BPS: u64 = 200;
+pub const DEFAULT_MIN_SOURCES: usize = 2;
+pub const DEFAULT_QUOTE_CURRENCY: &str = "USD";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OracleError {
+    /// The request never produced a response (network, CORS, DNS).
+    Http(String),
+    /// The exchange answered with a non-2xx status.
+    Status(u16),
+    /// The exchange answered with a body we cannot read a price from.
+    Malformed(String),
+    /// Fewer usable quotes than the policy requires.
+    InsufficientSources { usable: usize, required: usize },
+    /// The exchange has no market quoted in the pair's currency.
+    NoMarket { symbol: String, currency: String },
+}
+
+impl std::fmt::Display for OracleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OracleError::Http(why) => write!(f, "request failed: {}", why),
+            OracleError::Status(code) => write!(f, "HTTP {}", code),
+            OracleError::Malformed(why) => write!(f, "malformed quote: {}", why),
+            OracleError::InsufficientSources { usable, required } => {
+                write!(f, "{} usable quotes, {} required", usable, required)
+            }
+            OracleError::NoMarket { symbol, currency } => write!(f, "no {}/{} market", symbol, currency),
+        }
+    }
+}
+
+impl std::error::Error for OracleError {}
+
+/// Minimal GET transport: the response body of a 2xx answer.
+pub trait HttpGet {
+    fn get<'a>(&'a self, url: &'a str) -> LocalBoxFuture<'a, Result<String, OracleError>>;
+}
+
+/// What to price: an asset in a quote currency, plus any exchange market ids
+/// that differ from the adapters' naming rules.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pair {
+    pub symbol: String,
+    pub currency: String,
+    /// Oracle name to that exchange's market id, e.g. "kraken" → "XXBTZUSD".
+    /// The market must be quoted in `currency`.
+    pub markets: BTreeMap<String, String>,
+}
+
+impl Pair {
+    pub fn new(symbol: &str, currency: &str) -> Self {
+        Self { symbol: symbol.into(), currency: currency.into(), markets: BTreeMap::new() }
+    }
+
+    pub fn usd(symbol: &str) -> Self {
+        Self::new(symbol, DEFAULT_QUOTE_CURRENCY)
+    }
+
+    /// The configured market id for `oracle`, or the one its naming rule gives.
+    /// `None` when the exchange has no market in `currency`.
+    pub fn market(&self, oracle: &str) -> Option<String> {
+        if let Some(market) = self.markets.get(oracle) {
+            return Some(market.clone());
+        }
+        let (symbol, currency) = (self.symbol.as_str(), self.currency.as_str());
+        match oracle {
+            "coinbase" => Some(format!("{}-{}", symbol, currency)),
+            // Kraken lists bitcoin as XBT.
+            "kraken" => Some(format!("{}{}", if symbol == "BTC" { "XBT" } else { symbol }, currency)),
+            // Binance has no USD books, and USDT is not USD.
+            "binance" if currency == "USD" => None,
+            _ => Some(format!("{}{}", symbol, currency)),
+        }
+    }
+
+    /// The market id for `oracle`, or `OracleError::NoMarket`.
+    fn require_market(&self, oracle: &str) -> Result<String, OracleError> {
+        self.market(oracle)
+            .ok_or_else(|| OracleError::NoMarket { symbol: self.symbol.clone(), currency: self.currency.clone() })
+    }
+}
+
+/// One exchange's price for one asset.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Quote {
+    /// Adapter name, e.g. "coinbase".
+    pub source: String,
+    pub symbol: String,
+    /// Decimal string exactly as published by the exchange.
+    pub price: String,
+    /// When the exchange says the price was current, in Unix epoch milliseconds;
+    /// the fetch time for exchanges that publish no timestamp.
+    pub timestamp_ms: u64,
+}
+
+impl Quote {
+    fn new(source: &str, symbol: &str, price: String, timestamp_ms: u64) -> Result<Self, OracleError> {
+        parse_price(&price)?;
+        Ok(Self { source: source.into(), symbol: symbol.into(), price, timestamp_ms })
+    }
+
+    pub fn value(&self) -> f64 {
+        self.price.parse().unwrap_or(f64::NAN)
+    }
+}
+
+fn parse_price(price: &str) -> Result<f64, OracleError> {
+    match price.parse::<f64>() {
+        Ok(value) if value.is_finite() && value > 0.0 => Ok(value),
+        _ => Err(OracleError::Malformed(format!("{:?} is not a price", price))),
+    }
+}
+
+fn decode<T: serde::de::DeserializeOwned>(body: &str) -> Result<T, OracleError> {
+    serde_json::from_str(body).map_err(|e| OracleError::Malformed(e.to_string()))
+}
+
+/// A source of spot quotes.
+pub trait PriceOracle {
+    /// Also the key for this oracle's market ids in `Pair::markets`.
+    fn name(&self) -> &str;
+
+    /// Fetches the current quote for `pair`; `now_ms` stamps quotes that carry no time.
+    fn quote<'a>(&'a self, pair: &'a Pair, now_ms: u64) -> LocalBoxFuture<'a, Result<Quote, OracleError>>;
+}
+
+/* ===================== EXCHANGE ADAPTERS ===================== */
+
+/// Coinbase `/v2/prices/{SYM}-{CUR}/spot`. Untimestamped.
+pub struct CoinbaseOracle {
+    http: Rc<dyn HttpGet>,
+    base_url: String,
+}
+
+impl CoinbaseOracle {
+    pub const BASE_URL: &'static str = "https://api.coinbase.com";
+
+    pub fn new(http: Rc<dyn HttpGet>) -> Self {
+        Self::with_base_url(http, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(http: Rc<dyn HttpGet>, base_url: &str) -> Self {
+        Self { http, base_url: base_url.trim_end_matches('/').into() }
+    }
+}
+
+impl PriceOracle for CoinbaseOracle {
+    fn name(&self) -> &str {
+        "coinbase"
+    }
+
+    fn quote<'a>(&'a self, pair: &'a Pair, now_ms: u64) -> LocalBoxFuture<'a, Result<Quote, OracleError>> {
+        #[derive(Deserialize)]
+        struct Response {
+            data: Data,
+        }
+        #[derive(Deserialize)]
+        struct Data {
+            amount: String,
+        }
+
+        Box::pin(async move {
+            let url = format!("{}/v2/prices/{}/spot", self.base_url, pair.require_market(self.name())?);
+            let response: Response = decode(&self.http.get(&url).await?)?;
+            Quote::new(self.name(), &pair.symbol, response.data.amount, now_ms)
+        })
+    }
+}
+
+/// Kraken `/0/public/Ticker`, last trade price. Untimestamped.
+pub struct KrakenOracle {
+    http: Rc<dyn HttpGet>,
+    base_url: String,
+}
+
+impl KrakenOracle {
+    pub const BASE_URL: &'static str = "https://api.kraken.com";
+
+    pub fn new(http: Rc<dyn HttpGet>) -> Self {
+        Self::with_base_url(http, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(http: Rc<dyn HttpGet>, base_url: &str) -> Self {
+        Self { http, base_url: base_url.trim_end_matches('/').into() }
+    }
+}
+
+impl PriceOracle for KrakenOracle {
+    fn name(&self) -> &str {
+        "kraken"
+    }
+
+    fn quote<'a>(&'a self, pair: &'a Pair, now_ms: u64) -> LocalBoxFuture<'a, Result<Quote, OracleError>> {
+        #[derive(Deserialize)]
+        struct Response {
+            error: Vec<String>,
+            #[serde(default)]
+            result: std::collections::BTreeMap<String, Ticker>,
+        }
+        #[derive(Deserialize)]
+        struct Ticker {
+            /// Last trade: [price, lot volume].
+            c: Vec<String>,
+        }
+
+        Box::pin(async move {
+            let url = format!("{}/0/public/Ticker?pair={}", self.base_url, pair.require_market(self.name())?);
+            let response: Response = decode(&self.http.get(&url).await?)?;
+            if let Some(error) = response.error.first() {
+                return Err(OracleError::Malformed(error.clone()));
+            }
+            // The result is keyed by Kraken's internal pair name (XXBTZUSD), not the one we asked for.
+            let price = response
+                .result
+                .into_values()
+                .next()
+                .and_then(|ticker| ticker.c.into_iter().next())
+                .ok_or_else(|| OracleError::Malformed("no ticker in result".into()))?;
+            Quote::new(self.name(), &pair.symbol, price, now_ms)
+        })
+    }
+}
+
+/// Binance `/api/v3/ticker/24hr`, stamped with the ticker's `closeTime`. Binance
+/// has no USD markets, so it sits out USD pairs.
+pub struct BinanceOracle {
+    http: Rc<dyn HttpGet>,
+    base_url: String,
+}
+
+impl BinanceOracle {
+    pub const BASE_URL: &'static str = "https://api.binance.com";
+
+    pub fn new(http: Rc<dyn HttpGet>) -> Self {
+        Self::with_base_url(http, Self::BASE_URL)
+    }
+
+    pub fn with_base_url(http: Rc<dyn HttpGet>, base_url: &str) -> Self {
+        Self { http, base_url: base_url.trim_end_matches('/').into() }
+    }
+}
+
+impl PriceOracle for BinanceOracle {
+    fn name(&self) -> &str {
+        "binance"
+    }
+
+    fn quote<'a>(&'a self, pair: &'a Pair, _now_ms: u64) -> LocalBoxFuture<'a, Result<Quote, OracleError>> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            last_price: String,
+            close_time: u64,
+        }
+
+        Box::pin(async move {
+            let url = format!("{}/api/v3/ticker/24hr?symbol={}", self.base_url, pair.require_market(self.name())?);
+            let response: Response = decode(&self.http.get(&url).await?)?;
+            Quote::new(self.name(), &pair.symbol, response.last_price, response.close_time)
+        })
+    }
+}
+
+/* ===================== AGGREGATION ===================== */
+
+/// When a set of quotes is good enough to show.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OraclePolicy {
+    pub max_age_ms: u64,
+    pub max_deviation_bps: u64,
+    pub min_sources: usize,
+}
+
+impl Default for OraclePolicy {
+    fn default() -> Self {
+        Self {
+            max_age_ms: DEFAULT_MAX_QUOTE_AGE_MS,
+            max_deviation_bps: DEFAULT_MAX_DEVIATION_BPS,
+            min_sources: DEFAULT_MIN_SOURCES,
+        }
+    }
+}
+
+/// Why a source's quote did not count towards the median.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Rejection {
+    Failed(OracleError),
+    /// Older than `max_age_ms`.
+    Stale { age_ms: u64 },
+    /// Further than `max_deviation_bps` from the median of all fresh quotes.
+    Deviates { bps: u64 },
+}
+
+/// The median of the quotes that passed the policy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AggregatedPrice {
+    pub symbol: String,
+    pub currency: String,
+    /// Decimal string, at the precision of the most precise quote used.
+    pub price: String,
+    /// Timestamp of the oldest quote used: the price is no fresher than that.
+    pub as_of_ms: u64,
+    pub quotes: Vec<Quote>,
+    pub rejected: Vec<(String, Rejection)>,
+}
+
+impl AggregatedPrice {
+    pub fn age_ms(&self, now_ms: u64) -> u64 {
+        now_ms.saturating_sub(self.as_of_ms)
+    }
+
+    pub fn is_stale(&self, now_ms: u64, max_age_ms: u64) -> bool {
+        self.age_ms(now_ms) > max_age_ms
+    }
+
+    /// Re-aggregates with `quote` replacing this price's input from the same source,
+    /// as when a streamed tick supersedes the last polled quote.
+    pub fn with_quote(&self, quote: Quote, now_ms: u64, policy: &OraclePolicy) -> Result<AggregatedPrice, OracleError> {
+        let mut quotes: Vec<Quote> = self.quotes.iter().filter(|q| q.source != quote.source).cloned().collect();
+        quotes.push(quote);
+        let results = quotes.into_iter().map(|q| (q.source.clone(), Ok(q))).collect();
+        aggregate(&Pair::new(&self.symbol, &self.currency), results, now_ms, policy)
+    }
+
+    /// The attestation form of this price. `quote_id` hashes the canonical list
+    /// of component quotes, so an auditor holding them can show how the price was formed.
+    pub fn bind(&self) -> Result<PriceQuote, JcsError> {
+        let sources: Vec<&str> = self.quotes.iter().map(|q| q.source.as_str()).collect();
+        Ok(PriceQuote {
+            source: format!("median:{}", sources.join(",")),
+            price: self.price.clone(),
+            currency: self.currency.clone(),
+            quoted_at_ms: self.as_of_ms,
+            quote_id: hex::encode(Sha256::digest(jcs::to_canonical_vec(&self.quotes)?)),
+        })
+    }
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) { (sorted[mid - 1] + sorted[mid]) / 2.0 } else { sorted[mid] }
+}
+
+fn deviation_bps(value: f64, reference: f64) -> u64 {
+    ((value - reference).abs() / reference * 10_000.0).round() as u64
+}
+
+fn decimals(price: &str) -> usize {
+    price.split_once('.').map_or(0, |(_, fraction)| fraction.len())
+}
+
+/// Formats at `precision` decimals, keeping one more when averaging two middle
+/// quotes produced a half (100 and 101 give 100.5, not 100).
+fn format_price(value: f64, precision: usize) -> String {
+    let text = format!("{:.*}", precision + 1, value);
+    match text.strip_suffix('0') {
+        Some(trimmed) => trimmed.trim_end_matches('.').to_string(),
+        None => text,
+    }
+}
+
+/// Median of the fresh quotes in `results` that agree with each other.
+pub fn aggregate(
+    pair: &Pair,
+    results: Vec<(String, Result<Quote, OracleError>)>,
+    now_ms: u64,
+    policy: &OraclePolicy,
+) -> Result<AggregatedPrice, OracleError> {
+    let mut rejected = Vec::new();
+    let mut fresh = Vec::new();
+    for (source, result) in results {
+        match result {
+            Err(e) => rejected.push((source, Rejection::Failed(e))),
+            Ok(quote) => {
+                let age_ms = now_ms.saturating_sub(quote.timestamp_ms);
+                if age_ms > policy.max_age_ms {
+                    rejected.push((source, Rejection::Stale { age_ms }));
+                } else {
+                    fresh.push(quote);
+                }
+            }
+        }
+    }
+
+    let required = policy.min_sources.max(1);
+    let insufficient = |usable| OracleError::InsufficientSources { usable, required };
+    if fresh.len() < required {
+        return Err(insufficient(fresh.len()));
+    }
+
+    let mut values: Vec<f64> = fresh.iter().map(Quote::value).collect();
+    values.sort_by(f64::total_cmp);
+    let reference = median(&values);
+    let (quotes, outliers): (Vec<Quote>, Vec<Quote>) =
+        fresh.into_iter().partition(|q| deviation_bps(q.value(), reference) <= policy.max_deviation_bps);
+    for quote in outliers {
+        let bps = deviation_bps(quote.value(), reference);
+        rejected.push((quote.source, Rejection::Deviates { bps }));
+    }
+    if quotes.len() < required {
+        return Err(insufficient(quotes.len()));
+    }
+
+    let mut values: Vec<f64> = quotes.iter().map(Quote::value).collect();
+    values.sort_by(f64::total_cmp);
+    let precision = quotes.iter().map(|q| decimals(&q.price)).max().unwrap_or(0);
+    Ok(AggregatedPrice {
+        symbol: pair.symbol.clone(),
+        currency: pair.currency.clone(),
+        price: format_price(median(&values), precision),
+        as_of_ms: quotes.iter().map(|q| q.timestamp_ms).min().unwrap_or(now_ms),
+        quotes,
+        rejected,
+    })
+}
+
+/// Several oracles queried together under one policy.
+pub struct OracleSet {
+    oracles: Vec<Rc<dyn PriceOracle>>,
+    pub policy: OraclePolicy,
+}
+
+impl OracleSet {
+    pub fn new(oracles: Vec<Rc<dyn PriceOracle>>, policy: OraclePolicy) -> Self {
+        Self { oracles, policy }
+    }
+
+    /// Coinbase, Kraken and Binance over `http`, with the default policy.
+    pub fn public(http: Rc<dyn HttpGet>) -> Self {
+        Self::new(
+            vec![
+                Rc::new(CoinbaseOracle::new(http.clone())),
+                Rc::new(KrakenOracle::new(http.clone())),
+                Rc::new(BinanceOracle::new(http)),
+            ],
+            OraclePolicy::default(),
+        )
+    }
+
+    /// Queries every oracle with a market for `pair` concurrently and aggregates what comes back.
+    pub async fn price(&self, pair: &Pair, now_ms: u64) -> Result<AggregatedPrice, OracleError> {
+        let listed = self.oracles.iter().filter(|oracle| pair.market(oracle.name()).is_some());
+        let results = join_all(listed.map(|oracle| async move {
+            (oracle.name().to_string(), oracle.quote(pair, now_ms).await)
+        }))
+        .await;
+        aggregate(pair, results, now_ms, &self.policy)
+    }
+}
+
+/* ===================== REFRESH & STREAMING ===================== */
+
+pub const DEFAULT_REFRESH_MS: u64 = 15_000;
+/// Ceiling for the retry delay after repeated failures.
+pub const DEFAULT_MAX_BACKOFF_MS: u64 = 300_000;
+
+/// Polling cadence: a fixed interval while fetches succeed, doubling after each
+/// consecutive failure up to `max_backoff_ms`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RefreshSchedule {
+    pub interval_ms: u64,
+    pub max_backoff_ms: u64,
+    failures: u32,
+}
+
+impl RefreshSchedule {
+    pub fn new(interval_ms: u64, max_backoff_ms: u64) -> Self {
+        Self { interval_ms, max_backoff_ms, failures: 0 }
+    }
+
+    /// Records the outcome of a fetch and returns the delay before the next one.
+    pub fn next_delay_ms(&mut self, succeeded: bool) -> u64 {
+        if succeeded {
+            self.failures = 0;
+            return self.interval_ms;
+        }
+        self.failures = self.failures.saturating_add(1);
+        let factor = 1u64.checked_shl(self.failures).unwrap_or(u64::MAX);
+        self.interval_ms.saturating_mul(factor).min(self.max_backoff_ms.max(self.interval_ms))
+    }
+
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+}
+
+/// Coinbase Exchange market-data feed; its `ticker` channel pushes a message per trade.
+pub const COINBASE_FEED_URL: &str = "wss://ws-feed.exchange.coinbase.com";
+
+pub fn coinbase_subscribe_message(pairs: &[Pair]) -> String {
+    let products: Vec<String> = pairs.iter().filter_map(|p| p.market("coinbase")).collect();
+    serde_json::json!({ "type": "subscribe", "product_ids": products, "channels": ["ticker"] }).to_string()
+}
+
+/// Reads a `ticker` channel message as a Coinbase quote for one of `pairs`.
+/// Subscription confirmations, heartbeats, other products and anything
+/// unreadable yield `None`.
+pub fn parse_coinbase_ticker(message: &str, pairs: &[Pair], now_ms: u64) -> Option<Quote> {
+    #[derive(Deserialize)]
+    struct Ticker {
+        #[serde(rename = "type")]
+        kind: String,
+        product_id: String,
+        price: String,
+        time: Option<String>,
+    }
+
+    let ticker: Ticker = serde_json::from_str(message).ok()?;
+    if ticker.kind != "ticker" {
+        return None;
+    }
+    let pair = pairs.iter().find(|p| p.market("coinbase").as_ref() == Some(&ticker.product_id))?;
+    let timestamp_ms = ticker.time.as_deref().and_then(parse_rfc3339_ms).unwrap_or(now_ms);
+    Quote::new("coinbase", &pair.symbol, ticker.price, timestamp_ms).ok()
+}
+
+/// Parses the UTC form `YYYY-MM-DDTHH:MM:SS[.fraction]Z` into epoch milliseconds.
+pub fn parse_rfc3339_ms(time: &str) -> Option<u64> {
+    let time = time.strip_suffix('Z')?;
+    let (date, clock) = time.split_once('T')?;
+    let mut date_parts = date.splitn(3, '-').map(|p| p.parse::<u64>().ok());
+    let (year, month, day) = (date_parts.next()??, date_parts.next()??, date_parts.next()??);
+    let (hms, fraction) = clock.split_once('.').unwrap_or((clock, ""));
+    let mut clock_parts = hms.splitn(3, ':').map(|p| p.parse::<u64>().ok());
+    let (hour, minute, second) = (clock_parts.next()??, clock_parts.next()??, clock_parts.next()??);
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let millis = format!("{:0<3}", &fraction[..fraction.len().min(3)]).parse::<u64>().ok()?;
+
+    // Days since 1970-01-01 in the proleptic Gregorian calendar (Howard Hinnant's days_from_civil).
+    // Years are not bounded by the format, so anything that can overflow is checked.
+    let (y, m) = if month <= 2 { (year.checked_sub(1)?, month + 9) } else { (year, month - 3) };
+    let (era, year_of_era) = (y / 400, y % 400);
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + (153 * m + 2) / 5 + day - 1;
+    let days = era.checked_mul(146_097)?.checked_add(day_of_era)?.checked_sub(719_468)?;
+    let minutes = days.checked_mul(24)?.checked_add(hour)?.checked_mul(60)?.checked_add(minute)?;
+    minutes.checked_mul(60_000)?.checked_add(second * 1000 + millis)
+}
+
+/// Length of `month` (1-12) in the proleptic Gregorian calendar.
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use browser::{FetchHttp, TickerStream};
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use super::*;
+    use gloo_net::http::Request;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+    use web_sys::{MessageEvent, WebSocket};
+
+    /// `HttpGet` over the browser's `fetch`.
+    pub struct FetchHttp;
+
+    impl HttpGet for FetchHttp {
+        fn get<'a>(&'a self, url: &'a str) -> LocalBoxFuture<'a, Result<String, OracleError>> {
+            Box::pin(async move {
+                let response = Request::get(url).send().await.map_err(|e| OracleError::Http(e.to_string()))?;
+                if !response.ok() {
+                    return Err(OracleError::Status(response.status()));
+                }
+                response.text().await.map_err(|e| OracleError::Http(e.to_string()))
+            })
+        }
+    }
+
+    /// A live Coinbase ticker subscription. Dropping it closes the socket.
+    pub struct TickerStream {
+        socket: WebSocket,
+        _on_open: Closure<dyn FnMut()>,
+        _on_message: Closure<dyn FnMut(MessageEvent)>,
+    }
+
+    impl TickerStream {
+        /// Subscribes to `pairs` and calls `on_quote` for every tick.
+        pub fn coinbase(pairs: Vec<Pair>, on_quote: impl Fn(Quote) + 'static) -> Result<Self, OracleError> {
+            let socket = WebSocket::new(COINBASE_FEED_URL).map_err(|_| OracleError::Http("cannot open feed".into()))?;
+            let on_open = {
+                let (socket, subscribe) = (socket.clone(), coinbase_subscribe_message(&pairs));
+                Closure::<dyn FnMut()>::new(move || {
+                    let _ = socket.send_with_str(&subscribe);
+                })
+            };
+            let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                let quote = event
+                    .data()
+                    .as_string()
+                    .and_then(|m| parse_coinbase_ticker(&m, &pairs, js_sys::Date::now() as u64));
+                if let Some(quote) = quote {
+                    on_quote(quote);
+                }
+            });
+            socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+            socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+            Ok(Self { socket, _on_open: on_open, _on_message: on_message })
+        }
+    }
+
+    impl Drop for TickerStream {
+        fn drop(&mut self) {
+            self.socket.set_onopen(None);
+            self.socket.set_onmessage(None);
+            let _ = self.socket.close();
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
+
+use crate::attestation::{AttestationBody, IntentAttestation, SIGNING_CONTEXT};
+use crate::jcs::{self, JcsError};
+
+/* ===================== SCHEMA VERSIONS & MIGRATION ===================== */
+
+// Schema 1: flat record, no version field. The signature covers only
+//           {asset, nonce, timestamp_utc, wallet_pubkey, hold_duration_ms, entropy_hash}.
+// Schema 2: `{ body, signature }` envelope; the signature covers the whole body.
+//           An optional `wallet_signature` co-signs the same canonical bytes
+//           (directly for Solana, through EIP-712 `bodyHash` for Ethereum).
+//
+// Optional body fields may be added within a schema version: they are omitted
+// from the canonical body when absent, so earlier bodies keep verifying.
+// Fields added after schema 1 (`V2_ONLY_FIELDS`) are never covered by a v1
+// signature, so a v1 body carrying any of them is refused outright.
+
+/// Fields a schema 1 signature cannot cover, in body order, plus the envelope's
+/// `wallet_signature`.
+pub const V2_ONLY_FIELDS: [&str; 5] = ["quote", "wallet_chain_id", "hold_started_ms", "hold_ended_ms", "wallet_signature"];
+
+/// Flat layout written before the signed envelope existed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AttestationV1 {
+    pub asset_symbol: String,
+    pub wallet_pubkey: String,
+    pub biometric_proof: String,
+    pub hold_duration_ms: u64,
+    pub entropy_hash: String,
+    pub nonce: String,
+    pub timestamp_utc: u64,
+    pub signature: String,
+    #[serde(default)]
+    pub signer_pubkey: String,   // Absent on records sealed with the all-zero key
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    body: AttestationBody,
+    signature: String,
+    #[serde(default)]
+    wallet_signature: Option<String>,
+}
+
+/// Every stored attestation layout, tagged by `schema_version`
+/// (a record without one is schema 1). `V2` also holds v1 records that were
+/// already upgraded, whose body keeps `schema_version: 1`.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum VersionedAttestation {
+    V1(AttestationV1),
+    V2(Box<IntentAttestation>),
+}
+
+impl<'de> Deserialize<'de> for VersionedAttestation {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let version = match value.get("body") {
+            Some(body) => body
+                .get("schema_version")
+                .and_then(Value::as_u64)
+                .ok_or_else(|| D::Error::custom("body.schema_version missing"))?,
+            None => value.get("schema_version").and_then(Value::as_u64).unwrap_or(1),
+        };
+
+        match (version, value.get("body").is_some()) {
+            (1, false) => serde_json::from_value(value)
+                .map(VersionedAttestation::V1)
+                .map_err(D::Error::custom),
+            (1 | 2, true) => serde_json::from_value::<Envelope>(value)
+                .map_err(D::Error::custom)
+                .and_then(|e| {
+                    let attestation = IntentAttestation {
+                        body: e.body,
+                        signature: e.signature,
+                        wallet_signature: e.wallet_signature,
+                    };
+                    match unsigned_v1_field(&attestation) {
+                        Some(field) => Err(D::Error::custom(format!("schema_version 1 body carries v2-only field {}", field))),
+                        None => Ok(VersionedAttestation::V2(Box::new(attestation))),
+                    }
+                }),
+            (v, _) => Err(D::Error::custom(format!("unsupported schema_version {}", v))),
+        }
+    }
+}
+
+impl From<VersionedAttestation> for IntentAttestation {
+    fn from(versioned: VersionedAttestation) -> Self {
+        match versioned {
+            VersionedAttestation::V1(v1) => upgrade_v1(v1),
+            VersionedAttestation::V2(current) => *current,
+        }
+    }
+}
+
+/// Lifts a schema 1 record into the envelope shape. The body keeps
+/// `schema_version: 1` so verification still rebuilds the legacy signed bytes.
+pub fn upgrade_v1(v1: AttestationV1) -> IntentAttestation {
+    IntentAttestation {
+        body: AttestationBody {
+            schema_version: 1,
+            context: SIGNING_CONTEXT.to_string(),
+            asset_symbol: v1.asset_symbol,
+            quote: None,
+            wallet_pubkey: v1.wallet_pubkey,
+            wallet_chain_id: None,
+            biometric_proof: v1.biometric_proof,
+            hold_duration_ms: v1.hold_duration_ms,
+            hold_started_ms: None,
+            hold_ended_ms: None,
+            entropy_hash: v1.entropy_hash,
+            nonce: v1.nonce,
+            timestamp_utc: v1.timestamp_utc,
+            signer_pubkey: v1.signer_pubkey,
+        },
+        signature: v1.signature,
+        wallet_signature: None,
+    }
+}
+
+/// The first of `V2_ONLY_FIELDS` present on a schema 1 record, if any.
+pub fn unsigned_v1_field(attestation: &IntentAttestation) -> Option<&'static str> {
+    let body = &attestation.body;
+    if body.schema_version != 1 {
+        return None;
+    }
+    let present = [
+        body.quote.is_some(),
+        body.wallet_chain_id.is_some(),
+        body.hold_started_ms.is_some(),
+        body.hold_ended_ms.is_some(),
+        attestation.wallet_signature.is_some(),
+    ];
+    V2_ONLY_FIELDS.iter().zip(present).find_map(|(field, present)| present.then_some(*field))
+}
+
+/// The payload schema 1 signed. `context`, `biometric_proof` and
+/// `signer_pubkey` were never covered.
+pub(crate) fn v1_signing_message(body: &AttestationBody) -> Result<Vec<u8>, JcsError> {
+    jcs::to_canonical_vec(&json!({
+        "asset": body.asset_symbol,
+        "nonce": body.nonce,
+        "timestamp_utc": body.timestamp_utc,
+        "wallet_pubkey": body.wallet_pubkey,
+        "hold_duration_ms": body.hold_duration_ms,
+        "entropy_hash": body.entropy_hash,
+    }))
+}
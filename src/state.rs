@@ -0,0 +1,139 @@
+/* ===================== VAULT STATE MACHINE ===================== */
+
+// The vault flow as a closed set of states. Each state carries exactly the data
+// the next step needs, so combinations such as "receipt shown while locked" or
+// "unlocked without a wallet" cannot be represented. The Leptos view renders
+// from `VaultState` and only changes it through `VaultState::apply`.
+
+/// Where the user is in the vault flow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VaultState {
+    /// Vector 0: the device key is still encrypted at rest.
+    KeySealed { unsealing: bool },
+    /// Vector 1: device key ready, no wallet linked yet.
+    AwaitingWallet,
+    /// Vector 2: wallet linked, identity not yet verified.
+    AwaitingBiometric { wallet: String, scanning: bool },
+    /// Identity verified; vault data still hidden behind hold-to-reveal.
+    Locked { wallet: String },
+    /// Vault revealed; hold-to-authorize seals an attestation.
+    Unlocked { wallet: String },
+    /// An attestation was just sealed and its receipt is on screen.
+    Receipt { wallet: String },
+}
+
+/// Inputs that move the flow forward (or back, on failure).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VaultEvent {
+    UnsealStarted,
+    UnsealFailed,
+    KeyUnsealed,
+    WalletLinked(String),
+    ScanStarted,
+    ScanFailed,
+    IdentityVerified,
+    Revealed,
+    IntentSealed,
+    ReceiptDismissed,
+    /// The user unlinked the wallet, or the wallet disconnected.
+    WalletUnlinked,
+    /// The wallet switched to another account; identity must be re-verified for it.
+    WalletChanged(String),
+}
+
+/// An event that has no meaning in the current state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidTransition {
+    pub state: VaultState,
+    pub event: VaultEvent,
+}
+
+impl std::fmt::Display for InvalidTransition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} is not valid in {:?}", self.event, self.state)
+    }
+}
+
+impl std::error::Error for InvalidTransition {}
+
+impl Default for VaultState {
+    fn default() -> Self {
+        VaultState::KeySealed { unsealing: false }
+    }
+}
+
+impl VaultState {
+    /// Pure transition function: the next state, or `None` if `event` is invalid here.
+    pub fn transition(&self, event: &VaultEvent) -> Option<VaultState> {
+        use VaultEvent as E;
+        use VaultState as S;
+
+        let next = match (self, event) {
+            (S::KeySealed { unsealing: false }, E::UnsealStarted) => S::KeySealed { unsealing: true },
+            (S::KeySealed { unsealing: true }, E::UnsealFailed) => S::KeySealed { unsealing: false },
+            (S::KeySealed { unsealing: true }, E::KeyUnsealed) => S::AwaitingWallet,
+            (S::AwaitingWallet, E::WalletLinked(wallet)) if !wallet.is_empty() => {
+                S::AwaitingBiometric { wallet: wallet.clone(), scanning: false }
+            }
+            (S::AwaitingBiometric { wallet, scanning: false }, E::ScanStarted) => {
+                S::AwaitingBiometric { wallet: wallet.clone(), scanning: true }
+            }
+            (S::AwaitingBiometric { wallet, scanning: true }, E::ScanFailed) => {
+                S::AwaitingBiometric { wallet: wallet.clone(), scanning: false }
+            }
+            (S::AwaitingBiometric { wallet, scanning: true }, E::IdentityVerified) => {
+                S::Locked { wallet: wallet.clone() }
+            }
+            (S::Locked { wallet }, E::Revealed) => S::Unlocked { wallet: wallet.clone() },
+            (S::Unlocked { wallet }, E::IntentSealed) => S::Receipt { wallet: wallet.clone() },
+            (S::Receipt { wallet }, E::ReceiptDismissed) => S::Unlocked { wallet: wallet.clone() },
+            (linked, E::WalletUnlinked) if linked.wallet_linked() => S::AwaitingWallet,
+            (linked, E::WalletChanged(wallet)) if !wallet.is_empty() && linked.wallet().is_some_and(|w| w != wallet) => {
+                S::AwaitingBiometric { wallet: wallet.clone(), scanning: false }
+            }
+            _ => return None,
+        };
+        Some(next)
+    }
+
+    /// Applies `event` in place, leaving the state untouched if it is invalid.
+    pub fn apply(&mut self, event: VaultEvent) -> Result<(), InvalidTransition> {
+        match self.transition(&event) {
+            Some(next) => {
+                *self = next;
+                Ok(())
+            }
+            None => Err(InvalidTransition { state: self.clone(), event }),
+        }
+    }
+
+    /// The linked wallet public key, once there is one.
+    pub fn wallet(&self) -> Option<&str> {
+        match self {
+            VaultState::KeySealed { .. } | VaultState::AwaitingWallet => None,
+            VaultState::AwaitingBiometric { wallet, .. }
+            | VaultState::Locked { wallet }
+            | VaultState::Unlocked { wallet }
+            | VaultState::Receipt { wallet } => Some(wallet),
+        }
+    }
+
+    pub fn key_ready(&self) -> bool {
+        !matches!(self, VaultState::KeySealed { .. })
+    }
+
+    /// Step 1 of the indicator.
+    pub fn wallet_linked(&self) -> bool {
+        self.wallet().is_some()
+    }
+
+    /// Step 2 of the indicator.
+    pub fn identity_verified(&self) -> bool {
+        matches!(self, VaultState::Locked { .. } | VaultState::Unlocked { .. } | VaultState::Receipt { .. })
+    }
+
+    /// Step 3 of the indicator: vault data is visible.
+    pub fn unlocked(&self) -> bool {
+        matches!(self, VaultState::Unlocked { .. } | VaultState::Receipt { .. })
+    }
+}
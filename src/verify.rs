@@ -0,0 +1,426 @@
+use ed25519_dalek::{Signature, VerifyingKey, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use uuid::Uuid;
+
+use crate::attestation::{AttestationBody, IntentAttestation, CURRENT_SCHEMA_VERSION, SIGNING_CONTEXT};
+use crate::biometric::BiometricAssertion;
+use crate::hold::DEFAULT_MIN_HOLD_MS;
+use crate::eip712::{self, Eip712Error};
+use crate::merkle::{InclusionProof, MerkleError, SignedCheckpoint};
+use crate::schema;
+use crate::wallet::WalletKind;
+use crate::webauthn::{AssertionError, RegisteredCredential};
+
+/* ===================== OFFLINE ATTESTATION VERIFIER ===================== */
+
+/// Default lifetime of an attestation, in seconds.
+pub const DEFAULT_TTL_SECS: u64 = 300;
+
+/// Tolerated clock drift between the signing device and the verifier, in seconds.
+pub const DEFAULT_MAX_CLOCK_SKEW_SECS: u64 = 30;
+
+/// Acceptance rules applied on top of the signature check.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Policy {
+    /// Verifier wall clock (Unix seconds) used for the TTL check.
+    pub now_utc: u64,
+    /// Maximum age of `timestamp_utc`; `None` disables the TTL check (archival audits).
+    pub ttl_secs: Option<u64>,
+    pub max_clock_skew_secs: u64,
+    /// Shortest acceptable `hold_duration_ms`; `None` accepts any hold.
+    pub min_hold_ms: Option<u64>,
+    /// Credential `biometric_proof` must be a WebAuthn assertion from; `None`
+    /// leaves the proof unchecked (legacy records carry a plain marker string).
+    pub credential: Option<RegisteredCredential>,
+    /// Reject records the wallet did not co-sign. A co-signature that is present
+    /// is always checked.
+    pub require_wallet_signature: bool,
+    /// Longest allowed gap between the bound price quote and the seal; `None`
+    /// accepts records without a quote. A quote that is present is always checked.
+    pub max_quote_age_ms: Option<u64>,
+    /// Accept schema 1 records, whose signature leaves most of the body
+    /// unsigned. Off by default; only for auditing old logs on purpose.
+    pub allow_legacy_schema: bool,
+}
+
+impl Policy {
+    pub fn new(now_utc: u64) -> Self {
+        Self {
+            now_utc,
+            ttl_secs: Some(DEFAULT_TTL_SECS),
+            max_clock_skew_secs: DEFAULT_MAX_CLOCK_SKEW_SECS,
+            min_hold_ms: Some(DEFAULT_MIN_HOLD_MS),
+            credential: None,
+            require_wallet_signature: false,
+            max_quote_age_ms: None,
+            allow_legacy_schema: false,
+        }
+    }
+}
+
+/// Summary of an attestation that passed every check.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Verified {
+    pub schema_version: u32,
+    pub nonce: Uuid,
+    pub signer: VerifyingKey,
+    pub age_secs: u64,
+    /// signCount of the checked biometric assertion; store it as the new
+    /// `RegisteredCredential::sign_count`.
+    pub sign_count: Option<u32>,
+    /// `wallet_pubkey` co-signed the canonical body.
+    pub wallet_cosigned: bool,
+    /// Age of the bound price quote at seal time, in milliseconds.
+    pub quote_age_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerifyError {
+    /// `schema_version` is newer than this verifier understands.
+    UnsupportedSchema(u32),
+    /// A schema 1 record under a policy that refuses legacy records.
+    LegacySchema,
+    /// A schema 1 record carries a field its signature cannot cover.
+    UnsignedField(&'static str),
+    /// The body was sealed for a different signing context.
+    WrongContext(String),
+    /// The payload could not be canonicalized.
+    Canonicalization(String),
+    /// `signer_pubkey` is not a valid hex Ed25519 key.
+    MalformedSignerKey,
+    /// `signer_pubkey` differs from the key the caller trusts.
+    SignerMismatch { expected: String, found: String },
+    /// `signature` is not 64 hex-encoded bytes.
+    MalformedSignature,
+    /// The Ed25519 signature does not cover the canonical payload.
+    BadSignature,
+    /// `timestamp_utc` is further in the future than the allowed clock skew.
+    FromFuture { timestamp_utc: u64, now_utc: u64 },
+    /// `timestamp_utc` is older than the policy TTL.
+    Expired { age_secs: u64, ttl_secs: u64 },
+    /// `nonce` is not a lowercase hyphenated UUIDv4.
+    MalformedNonce(String),
+    /// The hold was shorter than the policy minimum.
+    HoldTooShort { held_ms: u64, min_ms: u64 },
+    /// Hold start/end times contradict the recorded duration or seal time.
+    InconsistentHold(String),
+    /// `biometric_proof` is not a valid assertion over this body.
+    Biometric(AssertionError),
+    /// The policy requires a wallet co-signature and the record has none.
+    MissingWalletSignature,
+    /// `wallet_pubkey` is neither a base58 Ed25519 key nor a 0x Ethereum address.
+    MalformedWalletKey,
+    /// `wallet_signature` is not a base58 Ed25519 or 0x secp256k1 signature.
+    MalformedWalletSignature,
+    /// An Ethereum co-signature without the `wallet_chain_id` its domain needs.
+    MissingWalletChain,
+    /// The wallet signature does not cover the canonical payload.
+    BadWalletSignature,
+    /// The policy bounds quote age and the record carries no price quote.
+    MissingQuote,
+    /// `quote.price` is not a positive decimal string.
+    MalformedQuote(String),
+    /// The quote is timestamped after the seal, beyond the allowed clock skew.
+    QuoteAfterSeal { quoted_at_ms: u64, timestamp_utc: u64 },
+    /// The quote was older than the policy allows when intent was sealed.
+    QuoteTooOld { age_ms: u64, max_age_ms: u64 },
+    /// The checkpoint is not signed by the trusted key.
+    BadCheckpointSignature,
+    /// The inclusion proof is for a different tree size than the checkpoint.
+    ProofSizeMismatch { proof_size: u64, tree_size: u64 },
+    /// The attestation is not under the checkpointed root.
+    NotIncluded(MerkleError),
+}
+
+impl std::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerifyError::UnsupportedSchema(v) => write!(f, "unsupported schema_version {}", v),
+            VerifyError::LegacySchema => write!(f, "legacy schema_version 1 records are not accepted"),
+            VerifyError::UnsignedField(field) => write!(f, "schema_version 1 record carries unsigned {}", field),
+            VerifyError::WrongContext(ctx) => write!(f, "signing context {:?} is not {:?}", ctx, SIGNING_CONTEXT),
+            VerifyError::Canonicalization(why) => write!(f, "cannot canonicalize payload: {}", why),
+            VerifyError::MalformedSignerKey => write!(f, "signer_pubkey is not a valid Ed25519 key"),
+            VerifyError::SignerMismatch { expected, found } => {
+                write!(f, "signed by {} but {} is trusted", found, expected)
+            }
+            VerifyError::MalformedSignature => write!(f, "signature is not 64 hex bytes"),
+            VerifyError::BadSignature => write!(f, "signature does not match canonical payload"),
+            VerifyError::FromFuture { timestamp_utc, now_utc } => {
+                write!(f, "timestamp {} is ahead of verifier clock {}", timestamp_utc, now_utc)
+            }
+            VerifyError::Expired { age_secs, ttl_secs } => {
+                write!(f, "attestation is {}s old, ttl is {}s", age_secs, ttl_secs)
+            }
+            VerifyError::MalformedNonce(nonce) => write!(f, "nonce {:?} is not a UUIDv4", nonce),
+            VerifyError::HoldTooShort { held_ms, min_ms } => {
+                write!(f, "hold of {} ms is shorter than {} ms", held_ms, min_ms)
+            }
+            VerifyError::InconsistentHold(why) => write!(f, "inconsistent hold timing: {}", why),
+            VerifyError::Biometric(why) => write!(f, "biometric proof rejected: {}", why),
+            VerifyError::MissingWalletSignature => write!(f, "wallet co-signature required"),
+            VerifyError::MalformedWalletKey => write!(f, "wallet_pubkey is not a Solana key or Ethereum address"),
+            VerifyError::MalformedWalletSignature => write!(f, "wallet_signature is malformed"),
+            VerifyError::MissingWalletChain => write!(f, "ethereum co-signature without wallet_chain_id"),
+            VerifyError::BadWalletSignature => write!(f, "wallet signature does not match canonical payload"),
+            VerifyError::MissingQuote => write!(f, "price quote required"),
+            VerifyError::MalformedQuote(price) => write!(f, "quoted price {:?} is not a decimal", price),
+            VerifyError::QuoteAfterSeal { quoted_at_ms, timestamp_utc } => {
+                write!(f, "quote at {} ms postdates seal at {}", quoted_at_ms, timestamp_utc)
+            }
+            VerifyError::QuoteTooOld { age_ms, max_age_ms } => {
+                write!(f, "quote was {} ms old at seal, limit is {} ms", age_ms, max_age_ms)
+            }
+            VerifyError::BadCheckpointSignature => write!(f, "checkpoint signature does not match"),
+            VerifyError::ProofSizeMismatch { proof_size, tree_size } => {
+                write!(f, "proof is for a tree of {} but the checkpoint covers {}", proof_size, tree_size)
+            }
+            VerifyError::NotIncluded(why) => write!(f, "attestation not in checkpoint: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Parses the hex `signer_pubkey` embedded in an attestation.
+/// Trusting this key only proves integrity, not which device produced the seal.
+pub fn embedded_signer(attestation: &IntentAttestation) -> Result<VerifyingKey, VerifyError> {
+    let bytes: [u8; PUBLIC_KEY_LENGTH] = hex::decode(&attestation.body.signer_pubkey)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or(VerifyError::MalformedSignerKey)?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_| VerifyError::MalformedSignerKey)
+}
+
+/// Rebuilds the canonical message and checks signature, wallet co-signature,
+/// signer, nonce, TTL, hold timing, the bound price quote and, when the policy
+/// names a credential, the biometric proof.
+pub fn verify_attestation(
+    attestation: &IntentAttestation,
+    key: &VerifyingKey,
+    policy: &Policy,
+) -> Result<Verified, VerifyError> {
+    let body = &attestation.body;
+    if body.schema_version == 0 || body.schema_version > CURRENT_SCHEMA_VERSION {
+        return Err(VerifyError::UnsupportedSchema(body.schema_version));
+    }
+    if body.schema_version == 1 {
+        if !policy.allow_legacy_schema {
+            return Err(VerifyError::LegacySchema);
+        }
+        // Deserialization already refuses these; bodies built in code are checked here.
+        if let Some(field) = schema::unsigned_v1_field(attestation) {
+            return Err(VerifyError::UnsignedField(field));
+        }
+    }
+    // Schema 1 never signed the context; upgraded records carry it unsigned.
+    if body.schema_version >= 2 && body.context != SIGNING_CONTEXT {
+        return Err(VerifyError::WrongContext(body.context.clone()));
+    }
+    let expected = hex::encode(key.to_bytes());
+    if !body.signer_pubkey.eq_ignore_ascii_case(&expected) {
+        return Err(VerifyError::SignerMismatch {
+            expected,
+            found: body.signer_pubkey.clone(),
+        });
+    }
+
+    let sig_bytes: [u8; SIGNATURE_LENGTH] = hex::decode(&attestation.signature)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or(VerifyError::MalformedSignature)?;
+    let message = body
+        .signing_message()
+        .map_err(|e| VerifyError::Canonicalization(e.to_string()))?;
+    key.verify_strict(&message, &Signature::from_bytes(&sig_bytes))
+        .map_err(|_| VerifyError::BadSignature)?;
+    let wallet_cosigned = match &attestation.wallet_signature {
+        Some(signature) => {
+            match WalletKind::of(&body.wallet_pubkey) {
+                WalletKind::Solana => check_solana_signature(&body.wallet_pubkey, signature, &message)?,
+                WalletKind::Evm => check_evm_signature(body, signature)?,
+            }
+            true
+        }
+        None if policy.require_wallet_signature => return Err(VerifyError::MissingWalletSignature),
+        None => false,
+    };
+
+    let nonce = parse_nonce(&body.nonce)?;
+    let age_secs = check_freshness(body.timestamp_utc, policy)?;
+    check_hold(body, policy)?;
+    let quote_age_ms = check_quote(body, policy)?;
+    let sign_count = match &policy.credential {
+        Some(credential) => Some(check_biometric(body, credential)?),
+        None => None,
+    };
+
+    Ok(Verified {
+        schema_version: body.schema_version,
+        nonce,
+        signer: *key,
+        age_secs,
+        sign_count,
+        wallet_cosigned,
+        quote_age_ms,
+    })
+}
+
+/// Solana wallets sign raw message bytes with the account's Ed25519 key.
+fn check_solana_signature(wallet_pubkey: &str, signature: &str, message: &[u8]) -> Result<(), VerifyError> {
+    let key_bytes: [u8; PUBLIC_KEY_LENGTH] = bs58::decode(wallet_pubkey)
+        .into_vec()
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or(VerifyError::MalformedWalletKey)?;
+    let wallet = VerifyingKey::from_bytes(&key_bytes).map_err(|_| VerifyError::MalformedWalletKey)?;
+    let sig_bytes: [u8; SIGNATURE_LENGTH] = WalletKind::Solana
+        .decode_signature(signature)
+        .and_then(|b| b.try_into().ok())
+        .ok_or(VerifyError::MalformedWalletSignature)?;
+    wallet
+        .verify_strict(message, &Signature::from_bytes(&sig_bytes))
+        .map_err(|_| VerifyError::BadWalletSignature)
+}
+
+/// Ethereum wallets sign the EIP-712 digest; the signer is recovered and
+/// compared with `wallet_pubkey`.
+fn check_evm_signature(body: &AttestationBody, signature: &str) -> Result<(), VerifyError> {
+    let expected = eip712::parse_address(&body.wallet_pubkey).map_err(|_| VerifyError::MalformedWalletKey)?;
+    let sig_bytes = WalletKind::Evm
+        .decode_signature(signature)
+        .ok_or(VerifyError::MalformedWalletSignature)?;
+    let digest = eip712::signing_digest(body).map_err(|e| match e {
+        Eip712Error::MissingChainId => VerifyError::MissingWalletChain,
+        other => VerifyError::Canonicalization(other.to_string()),
+    })?;
+    match eip712::recover_address(&digest, &sig_bytes) {
+        Ok(recovered) if recovered == expected => Ok(()),
+        Ok(_) | Err(Eip712Error::Unrecoverable) => Err(VerifyError::BadWalletSignature),
+        Err(_) => Err(VerifyError::MalformedWalletSignature),
+    }
+}
+
+fn parse_nonce(nonce: &str) -> Result<Uuid, VerifyError> {
+    let malformed = || VerifyError::MalformedNonce(nonce.to_string());
+    let uuid = Uuid::try_parse(nonce).map_err(|_| malformed())?;
+    // Only the exact form `Uuid::to_string` emits is accepted, so one nonce has one spelling.
+    if uuid.get_version_num() != 4 || uuid.hyphenated().to_string() != nonce {
+        return Err(malformed());
+    }
+    Ok(uuid)
+}
+
+fn check_freshness(timestamp_utc: u64, policy: &Policy) -> Result<u64, VerifyError> {
+    if timestamp_utc > policy.now_utc.saturating_add(policy.max_clock_skew_secs) {
+        return Err(VerifyError::FromFuture { timestamp_utc, now_utc: policy.now_utc });
+    }
+    let age_secs = policy.now_utc.saturating_sub(timestamp_utc);
+    match policy.ttl_secs {
+        Some(ttl_secs) if age_secs > ttl_secs => Err(VerifyError::Expired { age_secs, ttl_secs }),
+        _ => Ok(age_secs),
+    }
+}
+
+fn check_biometric(body: &AttestationBody, credential: &RegisteredCredential) -> Result<u32, VerifyError> {
+    let assertion = BiometricAssertion::from_proof(&body.biometric_proof)
+        .map_err(|e| VerifyError::Biometric(AssertionError::Malformed(e.to_string())))?;
+    let challenge = body
+        .biometric_challenge()
+        .map_err(|e| VerifyError::Canonicalization(e.to_string()))?;
+    credential.verify(&assertion, &challenge).map_err(VerifyError::Biometric)
+}
+
+/// Digits with an optional fractional part; no sign, exponent or padding.
+fn is_decimal(price: &str) -> bool {
+    let (whole, fraction) = price.split_once('.').unwrap_or((price, "0"));
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    digits(whole) && digits(fraction)
+}
+
+fn check_quote(body: &AttestationBody, policy: &Policy) -> Result<Option<u64>, VerifyError> {
+    // A schema 1 signature never covers a quote, so a legacy record has none to offer.
+    let Some(quote) = body.quote.as_ref().filter(|_| body.schema_version >= 2) else {
+        return match policy.max_quote_age_ms {
+            Some(_) => Err(VerifyError::MissingQuote),
+            None => Ok(None),
+        };
+    };
+    if !is_decimal(&quote.price) || quote.price.bytes().all(|b| b == b'0' || b == b'.') {
+        return Err(VerifyError::MalformedQuote(quote.price.clone()));
+    }
+    if quote.quoted_at_ms / 1000 > body.timestamp_utc.saturating_add(policy.max_clock_skew_secs) {
+        return Err(VerifyError::QuoteAfterSeal { quoted_at_ms: quote.quoted_at_ms, timestamp_utc: body.timestamp_utc });
+    }
+    let age_ms = body.timestamp_utc.saturating_mul(1000).saturating_sub(quote.quoted_at_ms);
+    match policy.max_quote_age_ms {
+        Some(max_age_ms) if age_ms > max_age_ms => Err(VerifyError::QuoteTooOld { age_ms, max_age_ms }),
+        _ => Ok(Some(age_ms)),
+    }
+}
+
+fn check_hold(body: &AttestationBody, policy: &Policy) -> Result<(), VerifyError> {
+    // Schema 1 signed only the duration; endpoints on such a body are not
+    // attested, so they can neither pass nor fail it.
+    let endpoints = match body.schema_version {
+        1 => (None, None),
+        _ => (body.hold_started_ms, body.hold_ended_ms),
+    };
+    match endpoints {
+        (None, None) => {}
+        (Some(started), Some(ended)) => {
+            if ended.checked_sub(started) != Some(body.hold_duration_ms) {
+                return Err(VerifyError::InconsistentHold(format!(
+                    "{} ms recorded for a {}..{} hold",
+                    body.hold_duration_ms, started, ended
+                )));
+            }
+            if ended / 1000 > body.timestamp_utc.saturating_add(policy.max_clock_skew_secs) {
+                return Err(VerifyError::InconsistentHold("hold ended after the seal".into()));
+            }
+        }
+        _ => return Err(VerifyError::InconsistentHold("only one hold endpoint recorded".into())),
+    }
+    match policy.min_hold_ms {
+        Some(min_ms) if body.hold_duration_ms < min_ms => {
+            Err(VerifyError::HoldTooShort { held_ms: body.hold_duration_ms, min_ms })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that `checkpoint` is signed by `key`, the device key the caller trusts.
+pub fn verify_checkpoint(checkpoint: &SignedCheckpoint, key: &VerifyingKey) -> Result<(), VerifyError> {
+    let expected = hex::encode(key.to_bytes());
+    if !checkpoint.signer_pubkey.eq_ignore_ascii_case(&expected) {
+        return Err(VerifyError::SignerMismatch { expected, found: checkpoint.signer_pubkey.clone() });
+    }
+    let sig_bytes: [u8; SIGNATURE_LENGTH] = hex::decode(&checkpoint.signature)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or(VerifyError::MalformedSignature)?;
+    let message = checkpoint
+        .checkpoint
+        .signing_message()
+        .map_err(|e| VerifyError::Canonicalization(e.to_string()))?;
+    key.verify_strict(&message, &Signature::from_bytes(&sig_bytes))
+        .map_err(|_| VerifyError::BadCheckpointSignature)
+}
+
+/// Checks that `attestation` is a leaf of the tree `checkpoint` commits to.
+/// Only membership is proven; the attestation itself still needs
+/// `verify_attestation`.
+pub fn verify_inclusion(
+    attestation: &IntentAttestation,
+    proof: &InclusionProof,
+    checkpoint: &SignedCheckpoint,
+    key: &VerifyingKey,
+) -> Result<(), VerifyError> {
+    verify_checkpoint(checkpoint, key)?;
+    let tree_size = checkpoint.checkpoint.tree_size;
+    if proof.tree_size != tree_size {
+        return Err(VerifyError::ProofSizeMismatch { proof_size: proof.tree_size, tree_size });
+    }
+    proof
+        .verify(attestation, &checkpoint.checkpoint.root_hash)
+        .map_err(VerifyError::NotIncluded)
+}
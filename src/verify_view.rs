@@ -0,0 +1,173 @@
+use ed25519_dalek::VerifyingKey;
+use leptos::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{DragEvent, File, HtmlInputElement};
+
+use crate::import::{self, AttestationFile, FileReport, ImportError};
+use crate::verify::{Policy, DEFAULT_TTL_SECS};
+
+/* ===================== VERIFY VIEW ===================== */
+
+/// Auditor tool: drop an attestation file (JSON, JSON Lines or a signed
+/// bundle) and every record is checked with `import::check_file`. Nothing
+/// leaves the browser. Results follow the trusted key and TTL fields live, so
+/// the same file can be re-checked without dropping it again.
+#[component]
+pub fn VerifyView() -> impl IntoView {
+    let (file, set_file) = create_signal(None::<(String, Result<AttestationFile, ImportError>)>);
+    let (key_hex, set_key_hex) = create_signal(String::new());
+    let (ttl, set_ttl) = create_signal(DEFAULT_TTL_SECS.to_string());
+    let (dragging, set_dragging) = create_signal(false);
+    // Schema 1 records leave most of the body unsigned; they fail unless asked for.
+    let (allow_legacy, set_allow_legacy) = create_signal(false);
+
+    let trusted_key = create_memo(move |_| {
+        let hex_key = key_hex.get();
+        let hex_key = hex_key.trim();
+        if hex_key.is_empty() {
+            return Ok(None);
+        }
+        hex::decode(hex_key)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .and_then(|b: [u8; 32]| VerifyingKey::from_bytes(&b).ok())
+            .map(Some)
+            .ok_or("TRUSTED KEY MUST BE 32 HEX BYTES")
+    });
+    // Blank TTL means an archival audit: no expiry check.
+    let ttl_secs = create_memo(move |_| {
+        let secs = ttl.get();
+        match secs.trim() {
+            "" => Ok(None),
+            secs => secs.parse().map(Some).map_err(|_| "TTL MUST BE WHOLE SECONDS"),
+        }
+    });
+    let report = create_memo(move |_| -> Option<Result<FileReport, String>> {
+        file.with(|file| {
+            let (_, parsed) = file.as_ref()?;
+            Some(match (parsed, trusted_key.get(), ttl_secs.get()) {
+                (Err(e), _, _) => Err(format!("{}", e).to_uppercase()),
+                (_, Err(e), _) | (_, _, Err(e)) => Err(e.into()),
+                (Ok(parsed), Ok(key), Ok(ttl_secs)) => {
+                    let mut policy = Policy::new((js_sys::Date::now() / 1000.0) as u64);
+                    policy.ttl_secs = ttl_secs;
+                    policy.allow_legacy_schema = allow_legacy.get();
+                    Ok(import::check_file(parsed, key.as_ref(), &policy))
+                }
+            })
+        })
+    });
+
+    let load = move |picked: File| {
+        spawn_local(async move {
+            let name = picked.name();
+            let parsed = match JsFuture::from(picked.text()).await {
+                Ok(text) => AttestationFile::parse(&text.as_string().unwrap_or_default()),
+                Err(e) => Err(ImportError::Parse(e.as_string().unwrap_or_else(|| "file not readable".into()))),
+            };
+            set_file.set(Some((name, parsed)));
+        });
+    };
+    let on_drop = move |ev: DragEvent| {
+        ev.prevent_default();
+        set_dragging.set(false);
+        if let Some(picked) = ev.data_transfer().and_then(|dt| dt.files()).and_then(|files| files.get(0)) {
+            load(picked);
+        }
+    };
+    let on_pick = move |ev: ev::Event| {
+        let input = event_target::<HtmlInputElement>(&ev);
+        if let Some(picked) = input.files().and_then(|files| files.get(0)) {
+            load(picked);
+        }
+        // Let the same file be picked again after it changes on disk.
+        input.set_value("");
+    };
+
+    view! {
+        <div class="verify-view">
+            <label
+                class="drop-zone"
+                class:dragging=dragging
+                on:dragover=move |ev: DragEvent| {
+                    ev.prevent_default();
+                    set_dragging.set(true);
+                }
+                on:dragleave=move |_| set_dragging.set(false)
+                on:drop=on_drop
+            >
+                <input type="file" accept=".json,.jsonl,.ndjson,application/json" on:change=on_pick />
+                {move || file.with(|f| match f {
+                    Some((name, _)) => name.to_uppercase(),
+                    None => "DROP JSON, JSONL OR BUNDLE FILE".into(),
+                })}
+            </label>
+            <input class="passphrase-input" type="text" spellcheck="false"
+                placeholder="TRUSTED KEY (HEX) · BLANK = EMBEDDED SIGNER"
+                prop:value=move || key_hex.get()
+                on:input=move |ev| set_key_hex.set(event_target_value(&ev))
+            />
+            <input class="passphrase-input" type="text" inputmode="numeric"
+                placeholder="TTL SECONDS · BLANK = NO EXPIRY"
+                prop:value=move || ttl.get()
+                on:input=move |ev| set_ttl.set(event_target_value(&ev))
+            />
+            <label class="verify-toggle">
+                <input type="checkbox"
+                    prop:checked=move || allow_legacy.get()
+                    on:change=move |ev| set_allow_legacy.set(event_target_checked(&ev))
+                />
+                "ACCEPT LEGACY SCHEMA 1 RECORDS (PARTLY UNSIGNED)"
+            </label>
+
+            {move || report.get().map(|report| match report {
+                Err(e) => view! { <div class="verify-summary failed">{e}</div> }.into_view(),
+                Ok(report) => {
+                    let total = report.records.len();
+                    let failed = report.failed_records();
+                    let summary = format!("{} RECORDS · {} PASSED · {} FAILED", total, total - failed, failed);
+                    // Without a trusted key a forger's file checks out against its own key.
+                    let unauthenticated = (!report.authenticated).then(|| view! {
+                        <div class="verify-bundle unauthenticated">"UNAUTHENTICATED: EMBEDDED SIGNER · INTEGRITY ONLY"</div>
+                    });
+                    let tick = if report.authenticated { "✓" } else { "✓?" };
+                    let bundle = report.bundle.clone().map(|verdict| match verdict {
+                        Ok(()) => view! { <div class="verify-bundle">"BUNDLE SIGNATURE, DIGEST & CHAIN HEAD ✓"</div> },
+                        Err(e) => view! { <div class="verify-bundle failed">{format!("BUNDLE: {}", e).to_uppercase()}</div> },
+                    });
+                    let passed = report.passed();
+                    let rows = report.records.into_iter().map(|record| {
+                        let ok = record.passed();
+                        let label = match record.seq {
+                            Some(seq) => format!("#{}", seq),
+                            None => format!("[{}]", record.index),
+                        };
+                        let body = &record.attestation.body;
+                        let nonce = body.nonce.get(0..8).unwrap_or_default().to_string();
+                        let reasons = record.issues.iter().map(|issue| {
+                            view! { <div class="verify-reason">{issue.to_string().to_uppercase()}</div> }
+                        }).collect_view();
+                        view! {
+                            <div class="verify-record" class:failed=!ok>
+                                <div class="log-entry">
+                                    <span>{label}</span>
+                                    <span>{body.asset_symbol.clone()}</span>
+                                    <span class="log-hash">{nonce}</span>
+                                    <span>{if ok { tick } else { "✗" }}</span>
+                                </div>
+                                {reasons}
+                            </div>
+                        }
+                    }).collect_view();
+                    view! {
+                        <div class="verify-summary" class:failed=!passed>{summary}</div>
+                        {unauthenticated}
+                        {bundle}
+                        <div class="verify-records">{rows}</div>
+                    }.into_view()
+                }
+            })}
+        </div>
+    }
+}
+
@@ -0,0 +1,721 @@
+/* ===================== WALLET REGISTRY ===================== */
+
+// Wallets announce themselves through the Wallet Standard
+// (`wallet-standard:register-wallet`); the legacy `window.solana` injection is
+// kept as a fallback for wallets that predate it, and an EIP-1193
+// `window.ethereum` provider is listed alongside them. The registry below is pure so
+// its dedup and selection rules can be tested natively; the browser glue that
+// feeds it lives at the bottom of this file.
+
+pub const FEATURE_CONNECT: &str = "standard:connect";
+pub const FEATURE_DISCONNECT: &str = "standard:disconnect";
+pub const FEATURE_EVENTS: &str = "standard:events";
+pub const FEATURE_SIGN_MESSAGE: &str = "solana:signMessage";
+/// Not a Wallet Standard feature: marks EIP-1193 providers that answer
+/// `eth_signTypedData_v4`.
+pub const FEATURE_SIGN_TYPED_DATA: &str = "eip712:signTypedData_v4";
+
+/// Signature scheme implied by a `wallet_pubkey`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WalletKind {
+    /// Base58 Ed25519 key; co-signs with `signMessage`.
+    Solana,
+    /// 0x-prefixed secp256k1 address; co-signs EIP-712 typed data.
+    Evm,
+}
+
+impl WalletKind {
+    pub fn of(wallet_pubkey: &str) -> Self {
+        if wallet_pubkey.starts_with("0x") { WalletKind::Evm } else { WalletKind::Solana }
+    }
+
+    /// Text form of a raw wallet signature in `wallet_signature`.
+    pub fn encode_signature(self, signature: &[u8]) -> String {
+        match self {
+            WalletKind::Solana => bs58::encode(signature).into_string(),
+            WalletKind::Evm => format!("0x{}", hex::encode(signature)),
+        }
+    }
+
+    pub fn decode_signature(self, signature: &str) -> Option<Vec<u8>> {
+        match self {
+            WalletKind::Solana => bs58::decode(signature).into_vec().ok(),
+            WalletKind::Evm => signature.strip_prefix("0x").and_then(|h| hex::decode(h).ok()),
+        }
+    }
+}
+
+/// How long `connect` waits for the wallet before giving up with `WalletError::Timeout`.
+pub const CONNECT_TIMEOUT_MS: u32 = 60_000;
+
+/// How long `co_sign` and `sign_message` wait for a signature. Longer than
+/// `CONNECT_TIMEOUT_MS` because the user has to read what they are signing.
+pub const SIGN_TIMEOUT_MS: u32 = 120_000;
+
+/// EIP-1193 error codes, also used by Solana wallets for rejected requests.
+pub const CODE_USER_REJECTED: i64 = 4001;
+pub const CODE_UNAUTHORIZED: i64 = 4100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WalletError {
+    /// No wallet with the requested name is installed.
+    NotInstalled,
+    /// The user dismissed or declined the request in the wallet.
+    UserRejected,
+    /// The wallet must be unlocked before it can answer.
+    Locked,
+    /// The wallet answered with something other than the expected shape.
+    MalformedResponse(String),
+    /// The wallet did not answer within `CONNECT_TIMEOUT_MS` or `SIGN_TIMEOUT_MS`.
+    Timeout,
+    Failed(String),
+}
+
+impl WalletError {
+    /// Classifies a rejected wallet request by its EIP-1193 `code`, falling back
+    /// to the message text for wallets that reject without one.
+    pub fn from_rejection(code: Option<i64>, message: &str) -> Self {
+        let lower = message.to_lowercase();
+        match code {
+            Some(CODE_USER_REJECTED) => WalletError::UserRejected,
+            Some(CODE_UNAUTHORIZED) => WalletError::Locked,
+            _ if lower.contains("user rejected") || lower.contains("user denied") => WalletError::UserRejected,
+            _ if lower.contains("locked") => WalletError::Locked,
+            _ if message.is_empty() => WalletError::Failed("request failed".into()),
+            _ => WalletError::Failed(message.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for WalletError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WalletError::NotInstalled => write!(f, "wallet not installed"),
+            WalletError::UserRejected => write!(f, "request rejected in wallet"),
+            WalletError::Locked => write!(f, "wallet is locked"),
+            WalletError::MalformedResponse(what) => write!(f, "unexpected wallet response: {}", what),
+            WalletError::Timeout => write!(f, "wallet did not respond"),
+            WalletError::Failed(why) => write!(f, "wallet error: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for WalletError {}
+
+/// How a wallet reached the registry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WalletSource {
+    /// Registered through the Wallet Standard.
+    Standard,
+    /// Found as a Phantom-style `window.solana` injection.
+    Injected,
+    /// Found as an EIP-1193 `window.ethereum` provider.
+    Eip1193,
+}
+
+/// What the vault needs to know about a wallet to list and use it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WalletInfo {
+    pub name: String,
+    /// Data URI supplied by the wallet, if any.
+    pub icon: Option<String>,
+    pub chains: Vec<String>,
+    pub features: Vec<String>,
+    pub source: WalletSource,
+}
+
+impl WalletInfo {
+    pub fn supports(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+
+    /// Can link an account and co-sign an attestation: a Solana wallet with
+    /// `signMessage`, or an Ethereum wallet with EIP-712 typed data.
+    pub fn is_usable(&self) -> bool {
+        let solana = self.supports(FEATURE_SIGN_MESSAGE) && self.chains.iter().any(|c| c.starts_with("solana:"));
+        let evm = self.supports(FEATURE_SIGN_TYPED_DATA) && self.chains.iter().any(|c| c.starts_with("eip155:"));
+        self.supports(FEATURE_CONNECT) && (solana || evm)
+    }
+}
+
+/// Discovered wallets, each with a handle `H` to the live wallet object.
+#[derive(Clone, Debug)]
+pub struct WalletRegistry<H> {
+    wallets: Vec<(WalletInfo, H)>,
+}
+
+impl<H> Default for WalletRegistry<H> {
+    fn default() -> Self {
+        Self { wallets: Vec::new() }
+    }
+}
+
+impl<H> WalletRegistry<H> {
+    /// Adds a wallet, keyed by name. A Standard registration replaces an injected
+    /// entry of the same name, never the other way round. Returns whether the
+    /// registry changed.
+    pub fn register(&mut self, info: WalletInfo, handle: H) -> bool {
+        match self.wallets.iter().position(|(w, _)| w.name == info.name) {
+            Some(i) if self.wallets[i].0.source == WalletSource::Injected && info.source == WalletSource::Standard => {
+                self.wallets[i] = (info, handle);
+                true
+            }
+            Some(_) => false,
+            None => {
+                self.wallets.push((info, handle));
+                true
+            }
+        }
+    }
+
+    /// Replaces the chains a registered wallet reports, e.g. when an EIP-1193
+    /// provider switches network. Returns whether the entry changed.
+    pub fn set_chains(&mut self, name: &str, chains: Vec<String>) -> bool {
+        match self.wallets.iter_mut().find(|(w, _)| w.name == name) {
+            Some((info, _)) if info.chains != chains => {
+                info.chains = chains;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn unregister(&mut self, name: &str) -> bool {
+        let before = self.wallets.len();
+        self.wallets.retain(|(w, _)| w.name != name);
+        self.wallets.len() != before
+    }
+
+    /// Wallets the vault can offer, in registration order.
+    pub fn usable(&self) -> Vec<WalletInfo> {
+        self.wallets.iter().filter(|(w, _)| w.is_usable()).map(|(w, _)| w.clone()).collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&H> {
+        self.wallets
+            .iter()
+            .find(|(w, _)| w.name == name && w.is_usable())
+            .map(|(_, h)| h)
+    }
+}
+
+/* ===================== BROWSER DISCOVERY ===================== */
+
+#[cfg(target_arch = "wasm32")]
+pub use browser::{discover, remember_wallet, remembered_wallet, WalletHandle, LINKED_WALLET_STORAGE_KEY};
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use std::cell::{Cell, RefCell};
+    use std::future::Future;
+    use std::rc::Rc;
+
+    use futures::future::{select, Either};
+    use gloo_timers::future::TimeoutFuture;
+
+    use js_sys::{Array, Function, Object, Promise, Reflect, Uint8Array};
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    use super::*;
+    use crate::attestation::AttestationBody;
+    use crate::eip712;
+
+    #[wasm_bindgen]
+    extern "C" {
+        /// Modern approach to access window.solana without deprecation warnings.
+        #[wasm_bindgen(js_namespace = window, js_name = solana)]
+        fn get_solana() -> JsValue;
+
+        #[wasm_bindgen(js_namespace = window, js_name = ethereum)]
+        fn get_ethereum() -> JsValue;
+    }
+
+    /// localStorage key holding the name of the last linked wallet, for silent reconnect.
+    pub const LINKED_WALLET_STORAGE_KEY: &str = "vext.linked_wallet";
+
+    type AccountListener = Rc<dyn Fn(Option<String>)>;
+
+    /// A live wallet object with a uniform connect / disconnect / co-sign surface.
+    #[derive(Clone)]
+    pub struct WalletHandle {
+        source: WalletSource,
+        wallet: JsValue,
+        /// The connected account: a `WalletAccount` object for Standard wallets
+        /// (they sign for the object, not an address), the address otherwise.
+        account: Rc<RefCell<Option<JsValue>>>,
+        /// EIP-155 chain of an EIP-1193 provider, kept current by `chainChanged`.
+        chain_id: Rc<Cell<Option<u64>>>,
+        /// Told about account switches and disconnects while the wallet is linked.
+        listener: Rc<RefCell<Option<AccountListener>>>,
+        /// Provider events are subscribed once per handle and routed to `listener`.
+        subscribed: Rc<Cell<bool>>,
+    }
+
+    impl WalletHandle {
+        fn new(source: WalletSource, wallet: JsValue) -> Self {
+            Self {
+                source,
+                wallet,
+                account: Rc::default(),
+                chain_id: Rc::default(),
+                listener: Rc::default(),
+                subscribed: Rc::default(),
+            }
+        }
+
+        /// The EIP-155 chain id, for Ethereum wallets once connected.
+        pub fn chain_id(&self) -> Option<u64> {
+            self.chain_id.get()
+        }
+
+        /// Connects and returns the address of the first account, base58 for
+        /// Solana and lowercase 0x-hex for Ethereum. Gives up after `CONNECT_TIMEOUT_MS`.
+        pub async fn connect(&self) -> Result<String, WalletError> {
+            with_timeout(CONNECT_TIMEOUT_MS, self.request_account(false)).await
+        }
+
+        /// Reconnects without prompting, which only succeeds if the wallet already
+        /// trusts this origin (`onlyIfTrusted`, `silent`, or `eth_accounts`).
+        pub async fn reconnect(&self) -> Result<String, WalletError> {
+            with_timeout(CONNECT_TIMEOUT_MS, self.request_account(true)).await
+        }
+
+        async fn request_account(&self, silent: bool) -> Result<String, WalletError> {
+            // An untrusted silent connect comes back empty; report it like a refusal.
+            let untrusted = |what: &str| if silent { WalletError::UserRejected } else { malformed(what) };
+            match self.source {
+                WalletSource::Standard => {
+                    let input = Object::new();
+                    let _ = Reflect::set(&input, &"silent".into(), &silent.into());
+                    let result = call_feature(&self.wallet, FEATURE_CONNECT, "connect", &[input.into()]).await?;
+                    let account = Reflect::get(&result, &"accounts".into())
+                        .ok()
+                        .map(|accounts| Array::from(&accounts).get(0))
+                        .filter(|a| a.is_object())
+                        .ok_or_else(|| untrusted("no accounts"))?;
+                    let address = Reflect::get(&account, &"address".into())
+                        .ok()
+                        .and_then(|a| a.as_string())
+                        .ok_or_else(|| malformed("account without address"))?;
+                    *self.account.borrow_mut() = Some(account);
+                    Ok(address)
+                }
+                WalletSource::Injected => {
+                    let options = Object::new();
+                    if silent {
+                        let _ = Reflect::set(&options, &"onlyIfTrusted".into(), &true.into());
+                    }
+                    let result = call_method(&self.wallet, "connect", &[options.into()]).await?;
+                    let public_key = Reflect::get(&result, &"publicKey".into())
+                        .ok()
+                        .filter(JsValue::is_object)
+                        .ok_or_else(|| malformed("no public key"))?;
+                    call_sync(&public_key, "toString", &[])?
+                        .as_string()
+                        .ok_or_else(|| malformed("public key is not printable"))
+                }
+                WalletSource::Eip1193 => {
+                    let method = if silent { "eth_accounts" } else { "eth_requestAccounts" };
+                    let accounts = eth_request(&self.wallet, method, &[]).await?;
+                    let address = Array::from(&accounts)
+                        .get(0)
+                        .as_string()
+                        .ok_or_else(|| untrusted("no accounts"))?
+                        .to_lowercase();
+                    let chain_id = eth_request(&self.wallet, "eth_chainId", &[]).await?;
+                    self.chain_id.set(chain_id.as_string().as_deref().and_then(parse_chain_id));
+                    *self.account.borrow_mut() = Some(address.as_str().into());
+                    Ok(address)
+                }
+            }
+        }
+
+        /// Routes account switches (`Some(new address)`) and disconnects (`None`)
+        /// to `listener`, replacing any previous one. `disconnect` removes it.
+        pub fn watch_account(&self, listener: impl Fn(Option<String>) + 'static) {
+            *self.listener.borrow_mut() = Some(Rc::new(listener));
+            if !self.subscribed.replace(true) {
+                subscribe_account_events(self);
+            }
+        }
+
+        fn account_changed(&self, account: Option<(JsValue, String)>) {
+            let address = account.map(|(account, address)| {
+                *self.account.borrow_mut() = Some(account);
+                address
+            });
+            if address.is_none() {
+                self.account.borrow_mut().take();
+            }
+            // Clone out of the cell so the listener may call `watch_account` or `disconnect`.
+            let listener = self.listener.borrow().clone();
+            if let Some(listener) = listener {
+                listener(address);
+            }
+        }
+
+        pub async fn disconnect(&self) -> Result<(), WalletError> {
+            self.listener.borrow_mut().take();
+            self.account.borrow_mut().take();
+            match self.source {
+                WalletSource::Standard if !has_feature(&self.wallet, FEATURE_DISCONNECT) => Ok(()),
+                WalletSource::Standard => {
+                    call_feature(&self.wallet, FEATURE_DISCONNECT, "disconnect", &[]).await.map(|_| ())
+                }
+                WalletSource::Injected => call_method(&self.wallet, "disconnect", &[]).await.map(|_| ()),
+                // EIP-1193 has no disconnect; forgetting the account is all a dapp can do.
+                WalletSource::Eip1193 => Ok(()),
+            }
+        }
+
+        /// Produces the raw co-signature over `body` that `IntentAttestation::with_wallet_signature` stores.
+        /// Gives up after `SIGN_TIMEOUT_MS`.
+        pub async fn co_sign(&self, body: &AttestationBody) -> Result<Vec<u8>, WalletError> {
+            match self.source {
+                WalletSource::Standard | WalletSource::Injected => {
+                    let message = body.signing_message().map_err(|e| WalletError::Failed(e.to_string()))?;
+                    self.sign_message(&message).await
+                }
+                WalletSource::Eip1193 => {
+                    let address = self.connected_account()?;
+                    let typed_data = eip712::typed_data(body).map_err(|e| WalletError::Failed(e.to_string()))?;
+                    let params = [address, typed_data.to_string().into()];
+                    let request = eth_request(&self.wallet, "eth_signTypedData_v4", &params);
+                    let signature = with_timeout(SIGN_TIMEOUT_MS, request).await?;
+                    signature
+                        .as_string()
+                        .and_then(|s| WalletKind::Evm.decode_signature(&s))
+                        .ok_or_else(|| malformed("no signature"))
+                }
+            }
+        }
+
+        fn connected_account(&self) -> Result<JsValue, WalletError> {
+            self.account.borrow().clone().ok_or_else(|| WalletError::Failed("not connected".into()))
+        }
+
+        /// Signs `message` with the connected account's key. Gives up after `SIGN_TIMEOUT_MS`.
+        pub async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>, WalletError> {
+            let bytes: JsValue = Uint8Array::from(message).into();
+            let signature = match self.source {
+                WalletSource::Standard => {
+                    let account = self.connected_account()?;
+                    let input = Object::new();
+                    let _ = Reflect::set(&input, &"account".into(), &account);
+                    let _ = Reflect::set(&input, &"message".into(), &bytes);
+                    let params = [input.into()];
+                    let request = call_feature(&self.wallet, FEATURE_SIGN_MESSAGE, "signMessage", &params);
+                    let outputs = with_timeout(SIGN_TIMEOUT_MS, request).await?;
+                    Reflect::get(&Array::from(&outputs).get(0), &"signature".into())
+                }
+                WalletSource::Injected => {
+                    let params = [bytes, "utf8".into()];
+                    let request = call_method(&self.wallet, "signMessage", &params);
+                    let signed = with_timeout(SIGN_TIMEOUT_MS, request).await?;
+                    Reflect::get(&signed, &"signature".into())
+                }
+                WalletSource::Eip1193 => return Err(WalletError::Failed("ethereum wallets co-sign typed data".into())),
+            };
+            signature
+                .ok()
+                .and_then(|s| s.dyn_into::<Uint8Array>().ok())
+                .map(|s| s.to_vec())
+                .ok_or_else(|| malformed("no signature"))
+        }
+    }
+
+    /// Subscribes to the wallet's own change events: `standard:events` `change`,
+    /// Phantom's `accountChanged`/`disconnect`, or EIP-1193 `accountsChanged`/`disconnect`.
+    fn subscribe_account_events(handle: &WalletHandle) {
+        let subscribe = |target: &JsValue, event: &str, callback: Box<dyn FnMut(JsValue)>| {
+            let callback = Closure::wrap(callback);
+            let _ = call_sync(target, "on", &[event.into(), callback.as_ref().clone()]);
+            callback.forget();
+        };
+        let on_disconnect = {
+            let handle = handle.clone();
+            Box::new(move |_: JsValue| handle.account_changed(None)) as Box<dyn FnMut(JsValue)>
+        };
+        match handle.source {
+            WalletSource::Standard => {
+                let Ok(events) = Reflect::get(&handle.wallet, &"features".into())
+                    .and_then(|f| Reflect::get(&f, &FEATURE_EVENTS.into()))
+                else {
+                    return;
+                };
+                let handle = handle.clone();
+                subscribe(
+                    &events,
+                    "change",
+                    Box::new(move |properties: JsValue| {
+                        // Only `accounts` changes concern us; chains and features may change alone.
+                        let Ok(accounts) = Reflect::get(&properties, &"accounts".into()) else { return };
+                        if accounts.is_undefined() {
+                            return;
+                        }
+                        let account = Array::from(&accounts).get(0);
+                        let address = Reflect::get(&account, &"address".into()).ok().and_then(|a| a.as_string());
+                        handle.account_changed(address.map(|address| (account, address)));
+                    }),
+                );
+            }
+            WalletSource::Injected => {
+                let on_account = {
+                    let handle = handle.clone();
+                    move |public_key: JsValue| {
+                        // Phantom passes null when it switched to an account this origin may not see.
+                        let address = call_sync(&public_key, "toString", &[]).ok().and_then(|a| a.as_string());
+                        handle.account_changed(address.map(|address| (address.as_str().into(), address)));
+                    }
+                };
+                subscribe(&handle.wallet, "accountChanged", Box::new(on_account));
+                subscribe(&handle.wallet, "disconnect", on_disconnect);
+            }
+            WalletSource::Eip1193 => {
+                let on_accounts = {
+                    let handle = handle.clone();
+                    move |accounts: JsValue| {
+                        let address = Array::from(&accounts).get(0).as_string().map(|a| a.to_lowercase());
+                        handle.account_changed(address.map(|address| (address.as_str().into(), address)));
+                    }
+                };
+                subscribe(&handle.wallet, "accountsChanged", Box::new(on_accounts));
+                subscribe(&handle.wallet, "disconnect", on_disconnect);
+            }
+        }
+    }
+
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window().and_then(|w| w.local_storage().ok().flatten())
+    }
+
+    /// The wallet to reconnect silently on the next visit, if one was linked.
+    pub fn remembered_wallet() -> Option<String> {
+        storage()?.get_item(LINKED_WALLET_STORAGE_KEY).ok().flatten()
+    }
+
+    pub fn remember_wallet(name: Option<&str>) {
+        if let Some(s) = storage() {
+            let _ = match name {
+                Some(name) => s.set_item(LINKED_WALLET_STORAGE_KEY, name),
+                None => s.remove_item(LINKED_WALLET_STORAGE_KEY),
+            };
+        }
+    }
+
+    type SharedRegistry = Rc<RefCell<WalletRegistry<WalletHandle>>>;
+
+    /// Starts Wallet Standard discovery and seeds the `window.solana` fallback.
+    /// `on_change` runs whenever a wallet is added or its chains change; the
+    /// listeners live for the page.
+    pub fn discover(on_change: impl Fn(Vec<WalletInfo>) + 'static) -> SharedRegistry {
+        let registry: SharedRegistry = Rc::default();
+        let on_change = Rc::new(on_change);
+        let Some(window) = web_sys::window() else { return registry };
+
+        // `register(wallet)`: wallets pass one wallet per call in practice, and the
+        // returned unregister callback is left undefined.
+        let register = {
+            let (registry, on_change) = (registry.clone(), on_change.clone());
+            Closure::<dyn FnMut(JsValue) -> JsValue>::new(move |wallet: JsValue| {
+                if let Some(info) = standard_info(&wallet) {
+                    let handle = WalletHandle::new(WalletSource::Standard, wallet);
+                    if registry.borrow_mut().register(info, handle) {
+                        on_change(registry.borrow().usable());
+                    }
+                }
+                JsValue::UNDEFINED
+            })
+        };
+        let api = Object::new();
+        let _ = Reflect::set(&api, &"register".into(), register.as_ref());
+        register.forget();
+
+        // Wallets loaded after us announce themselves with `register-wallet`...
+        let listener = {
+            let api = api.clone();
+            Closure::<dyn FnMut(web_sys::CustomEvent)>::new(move |event: web_sys::CustomEvent| {
+                if let Ok(callback) = event.detail().dyn_into::<Function>() {
+                    let _ = callback.call1(&JsValue::NULL, &api);
+                }
+            })
+        };
+        let _ = window.add_event_listener_with_callback("wallet-standard:register-wallet", listener.as_ref().unchecked_ref());
+        listener.forget();
+
+        // ...and wallets loaded before us answer `app-ready`.
+        let init = web_sys::CustomEventInit::new();
+        init.set_detail(&api);
+        if let Ok(event) = web_sys::CustomEvent::new_with_event_init_dict("wallet-standard:app-ready", &init) {
+            let _ = window.dispatch_event(&event);
+        }
+
+        let solana = get_solana();
+        if solana.is_object() {
+            let handle = WalletHandle::new(WalletSource::Injected, solana.clone());
+            if registry.borrow_mut().register(injected_info(&solana), handle) {
+                on_change(registry.borrow().usable());
+            }
+        }
+
+        let ethereum = get_ethereum();
+        if ethereum.is_object() {
+            let handle = WalletHandle::new(WalletSource::Eip1193, ethereum.clone());
+            let info = eip1193_info(&ethereum);
+            let chain = ChainTracker { handle: handle.clone(), name: info.name.clone(), registry: registry.clone(), on_change };
+            track_chain(&chain);
+            registry.borrow_mut().register(info, handle);
+            // Listed once the provider names its chain.
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(id) = eth_request(&ethereum, "eth_chainId", &[]).await {
+                    chain.changed(&id);
+                }
+            });
+        }
+        registry
+    }
+
+    /// Chains are filled in from `eth_chainId` by `ChainTracker`.
+    fn eip1193_info(ethereum: &JsValue) -> WalletInfo {
+        let is_metamask = Reflect::get(ethereum, &"isMetaMask".into()).ok().and_then(|p| p.as_bool()) == Some(true);
+        WalletInfo {
+            name: if is_metamask { "MetaMask" } else { "Ethereum Wallet" }.into(),
+            icon: None,
+            chains: Vec::new(),
+            features: vec![FEATURE_CONNECT.into(), FEATURE_EVENTS.into(), FEATURE_SIGN_TYPED_DATA.into()],
+            source: WalletSource::Eip1193,
+        }
+    }
+
+    /// Keeps an EIP-1193 wallet's chain current in its handle and registry entry.
+    #[derive(Clone)]
+    struct ChainTracker {
+        handle: WalletHandle,
+        name: String,
+        registry: SharedRegistry,
+        on_change: Rc<dyn Fn(Vec<WalletInfo>)>,
+    }
+
+    impl ChainTracker {
+        fn changed(&self, id: &JsValue) {
+            let chain_id = id.as_string().as_deref().and_then(parse_chain_id);
+            self.handle.chain_id.set(chain_id);
+            let chains = chain_id.map(|id| vec![format!("eip155:{}", id)]).unwrap_or_default();
+            if self.registry.borrow_mut().set_chains(&self.name, chains) {
+                (self.on_change)(self.registry.borrow().usable());
+            }
+        }
+    }
+
+    /// Follows `chainChanged` so attestations name the chain the wallet signs for.
+    fn track_chain(chain: &ChainTracker) {
+        let wallet = chain.handle.wallet.clone();
+        let chain = chain.clone();
+        let listener = Closure::<dyn FnMut(JsValue)>::new(move |id: JsValue| chain.changed(&id));
+        let _ = call_sync(&wallet, "on", &["chainChanged".into(), listener.as_ref().clone()]);
+        listener.forget();
+    }
+
+    /// EIP-1193 reports chain ids as 0x-prefixed hex quantities.
+    fn parse_chain_id(hex_id: &str) -> Option<u64> {
+        u64::from_str_radix(hex_id.strip_prefix("0x")?, 16).ok()
+    }
+
+    async fn eth_request(provider: &JsValue, method: &str, params: &[JsValue]) -> Result<JsValue, WalletError> {
+        let request = Object::new();
+        let _ = Reflect::set(&request, &"method".into(), &method.into());
+        let _ = Reflect::set(&request, &"params".into(), &params.iter().collect::<Array>());
+        call_method(provider, "request", &[request.into()]).await
+    }
+
+    fn standard_info(wallet: &JsValue) -> Option<WalletInfo> {
+        let name = Reflect::get(wallet, &"name".into()).ok()?.as_string()?;
+        let icon = Reflect::get(wallet, &"icon".into()).ok().and_then(|i| i.as_string());
+        let chains = Reflect::get(wallet, &"chains".into())
+            .ok()
+            .map(|c| Array::from(&c).iter().filter_map(|c| c.as_string()).collect())
+            .unwrap_or_default();
+        let features = Reflect::get(wallet, &"features".into())
+            .ok()
+            .filter(JsValue::is_object)
+            .map(|f| Object::keys(&Object::from(f)).iter().filter_map(|k| k.as_string()).collect())
+            .unwrap_or_default();
+        Some(WalletInfo { name, icon, chains, features, source: WalletSource::Standard })
+    }
+
+    /// Injected providers expose methods directly; map them onto feature names.
+    fn injected_info(solana: &JsValue) -> WalletInfo {
+        let is_phantom = Reflect::get(solana, &"isPhantom".into()).ok().and_then(|p| p.as_bool()) == Some(true);
+        let has = |method: &str| Reflect::get(solana, &method.into()).map(|m| m.is_function()).unwrap_or(false);
+        let features = [
+            (FEATURE_CONNECT, has("connect")),
+            (FEATURE_DISCONNECT, has("disconnect")),
+            (FEATURE_EVENTS, has("on")),
+            (FEATURE_SIGN_MESSAGE, has("signMessage")),
+        ];
+        WalletInfo {
+            name: if is_phantom { "Phantom" } else { "Solana Wallet" }.into(),
+            icon: None,
+            chains: vec!["solana:mainnet".into()],
+            features: features.iter().filter(|(_, ok)| *ok).map(|(f, _)| f.to_string()).collect(),
+            source: WalletSource::Injected,
+        }
+    }
+
+    fn has_feature(wallet: &JsValue, feature: &str) -> bool {
+        Reflect::get(wallet, &"features".into())
+            .and_then(|f| Reflect::has(&f, &feature.into()))
+            .unwrap_or(false)
+    }
+
+    fn malformed(what: &str) -> WalletError {
+        WalletError::MalformedResponse(what.into())
+    }
+
+    /// Maps a thrown or rejected value onto `WalletError` by its EIP-1193 shape.
+    fn classify(err: JsValue) -> WalletError {
+        let code = Reflect::get(&err, &"code".into()).ok().and_then(|c| c.as_f64()).map(|c| c as i64);
+        let message = Reflect::get(&err, &"message".into())
+            .ok()
+            .and_then(|m| m.as_string())
+            .or_else(|| err.as_string())
+            .unwrap_or_default();
+        WalletError::from_rejection(code, &message)
+    }
+
+    async fn with_timeout<T>(ms: u32, request: impl Future<Output = Result<T, WalletError>>) -> Result<T, WalletError> {
+        match select(Box::pin(request), TimeoutFuture::new(ms)).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => Err(WalletError::Timeout),
+        }
+    }
+
+    fn call_sync(target: &JsValue, method: &str, args: &[JsValue]) -> Result<JsValue, WalletError> {
+        let function = Reflect::get(target, &method.into())
+            .ok()
+            .and_then(|f| f.dyn_into::<Function>().ok())
+            .ok_or_else(|| malformed(&format!("no {} method", method)))?;
+        function.apply(target, &args.iter().collect::<Array>()).map_err(classify)
+    }
+
+    async fn call_method(target: &JsValue, method: &str, args: &[JsValue]) -> Result<JsValue, WalletError> {
+        let promise = call_sync(target, method, args)?;
+        JsFuture::from(Promise::resolve(&promise)).await.map_err(classify)
+    }
+
+    async fn call_feature(
+        wallet: &JsValue,
+        feature: &str,
+        method: &str,
+        args: &[JsValue],
+    ) -> Result<JsValue, WalletError> {
+        let implementation = Reflect::get(wallet, &"features".into())
+            .and_then(|f| Reflect::get(&f, &feature.into()))
+            .ok()
+            .filter(JsValue::is_object)
+            .ok_or_else(|| malformed(&format!("no {} feature", feature)))?;
+        call_method(&implementation, method, args).await
+    }
+}
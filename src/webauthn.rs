@@ -0,0 +1,257 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as B64URL, Engine};
+use ciborium::Value;
+use serde::{Deserialize, Serialize};
+use p256::ecdsa::{signature::Verifier, DerSignature, VerifyingKey};
+use p256::EncodedPoint;
+use sha2::{Digest, Sha256};
+
+use crate::biometric::{BiometricAssertion, CollectedClientData, FLAG_USER_PRESENT, FLAG_USER_VERIFIED};
+
+/* ===================== WEBAUTHN ASSERTION VERIFICATION ===================== */
+
+// Relying-party checks from WebAuthn Level 2 §7.2 for the one algorithm VEXT
+// registers: ES256 (COSE alg -7) on P-256.
+
+const COSE_KTY: i128 = 1;
+const COSE_ALG: i128 = 3;
+const COSE_EC2_CRV: i128 = -1;
+const COSE_EC2_X: i128 = -2;
+const COSE_EC2_Y: i128 = -3;
+const COSE_KTY_EC2: i128 = 2;
+const COSE_ALG_ES256: i128 = -7;
+const COSE_CRV_P256: i128 = 1;
+
+/// Authenticator data flag: attested credential data follows the sign count.
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssertionError {
+    /// `biometric_proof` is not a serialized WebAuthn assertion.
+    Malformed(String),
+    /// The COSE public key is not an ES256 P-256 key.
+    UnsupportedKey(String),
+    /// The assertion was made by a different credential.
+    UnknownCredential,
+    /// `clientDataJSON` is not from `navigator.credentials.get`.
+    WrongType(String),
+    /// The signed challenge is not the hash of this attestation body.
+    ChallengeMismatch,
+    /// The assertion was made on a page outside the allowed origins.
+    OriginNotAllowed(String),
+    /// The authenticator was scoped to a different relying party.
+    RpIdMismatch,
+    UserNotPresent,
+    UserNotVerified,
+    /// The ECDSA signature does not cover authenticatorData and clientDataJSON.
+    BadSignature,
+    /// The signature counter did not advance, suggesting a cloned authenticator.
+    SignCountRegressed { stored: u32, found: u32 },
+}
+
+impl std::fmt::Display for AssertionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssertionError::Malformed(why) => write!(f, "malformed assertion: {}", why),
+            AssertionError::UnsupportedKey(why) => write!(f, "unsupported credential key: {}", why),
+            AssertionError::UnknownCredential => write!(f, "assertion made by an unknown credential"),
+            AssertionError::WrongType(kind) => write!(f, "client data type {:?} is not webauthn.get", kind),
+            AssertionError::ChallengeMismatch => write!(f, "challenge is not the attestation body hash"),
+            AssertionError::OriginNotAllowed(origin) => write!(f, "origin {:?} is not allowed", origin),
+            AssertionError::RpIdMismatch => write!(f, "rpIdHash does not match the relying party"),
+            AssertionError::UserNotPresent => write!(f, "user presence flag not set"),
+            AssertionError::UserNotVerified => write!(f, "user verification flag not set"),
+            AssertionError::BadSignature => write!(f, "assertion signature is invalid"),
+            AssertionError::SignCountRegressed { stored, found } => {
+                write!(f, "sign count {} does not exceed stored {}", found, stored)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AssertionError {}
+
+/// Portable form of a registered credential, base64url without padding. This is
+/// what a vault exports and what `vext-verify --credential` reads.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CredentialRecord {
+    pub credential_id: String,
+    /// ES256 COSE_Key.
+    pub public_key: String,
+}
+
+impl CredentialRecord {
+    pub fn new(credential_id: &[u8], cose_key: &[u8]) -> Self {
+        Self { credential_id: B64URL.encode(credential_id), public_key: B64URL.encode(cose_key) }
+    }
+
+    pub fn credential_id_bytes(&self) -> Result<Vec<u8>, AssertionError> {
+        B64URL
+            .decode(&self.credential_id)
+            .map_err(|_| AssertionError::Malformed("credential_id is not base64url".into()))
+    }
+
+    /// Trusts this credential for `rp_id`, with no signCount seen yet.
+    pub fn register(&self, rp_id: &str) -> Result<RegisteredCredential, AssertionError> {
+        let cose_key = B64URL
+            .decode(&self.public_key)
+            .map_err(|_| AssertionError::Malformed("public_key is not base64url".into()))?;
+        RegisteredCredential::from_cose(&self.credential_id_bytes()?, &cose_key, rp_id)
+    }
+}
+
+/// A platform credential the verifier trusts, as captured at registration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegisteredCredential {
+    pub credential_id: Vec<u8>,
+    pub public_key: VerifyingKey,
+    /// Relying party the credential is scoped to, e.g. "vault.vext.com".
+    pub rp_id: String,
+    /// Exact origins assertions may come from, e.g. "https://vault.vext.com".
+    pub allowed_origins: Vec<String>,
+    /// Highest signCount already accepted; 0 for a fresh credential.
+    pub sign_count: u32,
+}
+
+impl RegisteredCredential {
+    /// Builds a credential whose only allowed origin is `https://{rp_id}`.
+    pub fn from_cose(credential_id: &[u8], cose_key: &[u8], rp_id: &str) -> Result<Self, AssertionError> {
+        Ok(Self {
+            credential_id: credential_id.to_vec(),
+            public_key: decode_cose_key(cose_key)?,
+            rp_id: rp_id.to_string(),
+            allowed_origins: vec![format!("https://{}", rp_id)],
+            sign_count: 0,
+        })
+    }
+
+    /// Checks `assertion` against this credential and the expected `challenge`.
+    /// Returns the new signCount, which the caller should store.
+    pub fn verify(&self, assertion: &BiometricAssertion, challenge: &[u8]) -> Result<u32, AssertionError> {
+        let malformed = |e: crate::biometric::BiometricError| AssertionError::Malformed(e.to_string());
+        let credential_id = B64URL
+            .decode(&assertion.credential_id)
+            .map_err(|_| AssertionError::Malformed("credential_id is not base64url".into()))?;
+        if credential_id != self.credential_id {
+            return Err(AssertionError::UnknownCredential);
+        }
+
+        let client_data_json = assertion.client_data_bytes().map_err(malformed)?;
+        let client_data: CollectedClientData = serde_json::from_slice(&client_data_json)
+            .map_err(|e| AssertionError::Malformed(format!("clientDataJSON: {}", e)))?;
+        if client_data.kind != "webauthn.get" {
+            return Err(AssertionError::WrongType(client_data.kind));
+        }
+        if B64URL.decode(&client_data.challenge).ok().as_deref() != Some(challenge) {
+            return Err(AssertionError::ChallengeMismatch);
+        }
+        if !self.allowed_origins.contains(&client_data.origin) {
+            return Err(AssertionError::OriginNotAllowed(client_data.origin));
+        }
+
+        let auth_data = assertion.authenticator_data_bytes().map_err(malformed)?;
+        if auth_data[..32] != Sha256::digest(self.rp_id.as_bytes())[..] {
+            return Err(AssertionError::RpIdMismatch);
+        }
+        let flags = auth_data[32];
+        if flags & FLAG_USER_PRESENT == 0 {
+            return Err(AssertionError::UserNotPresent);
+        }
+        if flags & FLAG_USER_VERIFIED == 0 {
+            return Err(AssertionError::UserNotVerified);
+        }
+
+        let signature = assertion
+            .signature_bytes()
+            .ok()
+            .and_then(|der| DerSignature::from_bytes(&der).ok())
+            .ok_or(AssertionError::BadSignature)?;
+        let signed = [auth_data.as_slice(), Sha256::digest(&client_data_json).as_slice()].concat();
+        self.public_key
+            .verify(&signed, &signature)
+            .map_err(|_| AssertionError::BadSignature)?;
+
+        // Authenticators without a counter report 0 forever; anything else must advance.
+        let found = u32::from_be_bytes(auth_data[33..37].try_into().expect("length checked on decode"));
+        if (found != 0 || self.sign_count != 0) && found <= self.sign_count {
+            return Err(AssertionError::SignCountRegressed { stored: self.sign_count, found });
+        }
+        Ok(found)
+    }
+}
+
+/// Parses an ES256 COSE_Key (RFC 9053 §7.1.1) into a P-256 verifying key.
+pub fn decode_cose_key(cose_key: &[u8]) -> Result<VerifyingKey, AssertionError> {
+    let unsupported = |why: &str| AssertionError::UnsupportedKey(why.to_string());
+    let value: Value = ciborium::de::from_reader(cose_key)
+        .map_err(|e| AssertionError::Malformed(format!("COSE key: {}", e)))?;
+    let map = value.as_map().ok_or_else(|| unsupported("not a CBOR map"))?;
+    let field = |label: i128| {
+        map.iter()
+            .find(|(k, _)| k.as_integer().map(i128::from) == Some(label))
+            .map(|(_, v)| v)
+    };
+    let int = |label: i128| field(label).and_then(Value::as_integer).map(i128::from);
+
+    if int(COSE_KTY) != Some(COSE_KTY_EC2) {
+        return Err(unsupported("kty is not EC2"));
+    }
+    if int(COSE_ALG) != Some(COSE_ALG_ES256) {
+        return Err(unsupported("alg is not ES256"));
+    }
+    if int(COSE_EC2_CRV) != Some(COSE_CRV_P256) {
+        return Err(unsupported("crv is not P-256"));
+    }
+    let coordinate = |label: i128| {
+        field(label)
+            .and_then(Value::as_bytes)
+            .filter(|b| b.len() == 32)
+            .ok_or_else(|| unsupported("coordinate is not 32 bytes"))
+    };
+    let point = EncodedPoint::from_affine_coordinates(
+        coordinate(COSE_EC2_X)?.as_slice().into(),
+        coordinate(COSE_EC2_Y)?.as_slice().into(),
+        false,
+    );
+    VerifyingKey::from_encoded_point(&point).map_err(|_| unsupported("point is not on P-256"))
+}
+
+/// Encodes a P-256 verifying key as an ES256 COSE_Key.
+pub fn encode_cose_key(key: &VerifyingKey) -> Vec<u8> {
+    let point = key.to_encoded_point(false);
+    let map = Value::Map(vec![
+        (Value::from(COSE_KTY), Value::from(COSE_KTY_EC2)),
+        (Value::from(COSE_ALG), Value::from(COSE_ALG_ES256)),
+        (Value::from(COSE_EC2_CRV), Value::from(COSE_CRV_P256)),
+        (Value::from(COSE_EC2_X), Value::Bytes(point.x().expect("uncompressed point").to_vec())),
+        (Value::from(COSE_EC2_Y), Value::Bytes(point.y().expect("uncompressed point").to_vec())),
+    ]);
+    let mut out = Vec::new();
+    ciborium::ser::into_writer(&map, &mut out).expect("writing CBOR to a Vec cannot fail");
+    out
+}
+
+/// Extracts the credential id and COSE public key from a registration
+/// `attestationObject` (WebAuthn §6.5). The attestation statement is not checked.
+pub fn parse_attestation_object(attestation_object: &[u8]) -> Result<(Vec<u8>, Vec<u8>), AssertionError> {
+    let malformed = |why: &str| AssertionError::Malformed(why.to_string());
+    let value: Value = ciborium::de::from_reader(attestation_object)
+        .map_err(|e| AssertionError::Malformed(format!("attestationObject: {}", e)))?;
+    let auth_data = value
+        .as_map()
+        .and_then(|m| m.iter().find(|(k, _)| k.as_text() == Some("authData")))
+        .and_then(|(_, v)| v.as_bytes())
+        .ok_or_else(|| malformed("attestationObject has no authData"))?;
+
+    // rpIdHash (32) | flags (1) | signCount (4) | aaguid (16) | idLen (2) | id | COSE key
+    if auth_data.len() < 55 || auth_data[32] & FLAG_ATTESTED_CREDENTIAL == 0 {
+        return Err(malformed("authData carries no attested credential"));
+    }
+    let id_len = u16::from_be_bytes([auth_data[53], auth_data[54]]) as usize;
+    let key_start = 55 + id_len;
+    if auth_data.len() <= key_start {
+        return Err(malformed("authData credential truncated"));
+    }
+    let credential_id = auth_data[55..key_start].to_vec();
+    let public_key = decode_cose_key(&auth_data[key_start..])?;
+    Ok((credential_id, encode_cose_key(&public_key)))
+}
@@ -0,0 +1,77 @@
+use vext_vault::assets::{AssetConfig, AssetError, AssetRegistry};
+use vext_vault::oracle::Pair;
+
+#[test]
+fn defaults_offer_btc_eth_sol_in_usd() {
+    let registry = AssetRegistry::default();
+    let symbols: Vec<&str> = registry.iter().map(|a| a.symbol.as_str()).collect();
+    assert_eq!(symbols, ["BTC", "ETH", "SOL"]);
+    assert_eq!(registry.first().symbol, "BTC");
+
+    let eth = registry.get("ETH").unwrap();
+    assert_eq!((eth.decimals, eth.chain.as_str(), eth.quote_currency.as_str()), (18, "ethereum", "USD"));
+    assert_eq!(eth.pair(), Pair::usd("ETH"));
+    assert!(registry.get("DOGE").is_none());
+}
+
+#[test]
+fn loads_assets_from_json_config() {
+    let registry = AssetRegistry::from_json(
+        r#"[
+            {"symbol": "BTC", "name": "Bitcoin", "decimals": 8, "chain": "bitcoin",
+             "quote_currency": "EUR", "oracle_ids": {"kraken": "XXBTZEUR"}},
+            {"symbol": "LINK", "name": "Chainlink", "decimals": 18, "chain": "ethereum"}
+        ]"#,
+    )
+    .unwrap();
+    assert_eq!(registry.len(), 2);
+
+    let btc = registry.get("BTC").unwrap().pair();
+    assert_eq!(btc.currency, "EUR");
+    assert_eq!(btc.market("kraken").as_deref(), Some("XXBTZEUR"));
+    assert_eq!(btc.market("coinbase").as_deref(), Some("BTC-EUR"));
+    assert_eq!(btc.market("binance").as_deref(), Some("BTCEUR"));
+
+    // Quote currency defaults to USD.
+    let link = registry.get("LINK").unwrap();
+    assert_eq!(link.quote_currency, "USD");
+    assert_eq!(link.pair().market("kraken").as_deref(), Some("LINKUSD"));
+}
+
+#[test]
+fn default_market_ids_follow_each_exchange() {
+    let btc = Pair::usd("BTC");
+    assert_eq!(
+        (btc.market("coinbase"), btc.market("kraken")),
+        (Some("BTC-USD".into()), Some("XBTUSD".into()))
+    );
+    assert_eq!(Pair::usd("SOL").market("kraken").as_deref(), Some("SOLUSD"));
+
+    // Binance only has USDT books, which are not USD ones.
+    assert_eq!(btc.market("binance"), None);
+    assert_eq!(Pair::new("BTC", "USDT").market("binance").as_deref(), Some("BTCUSDT"));
+}
+
+#[test]
+fn rejects_bad_configs() {
+    assert!(matches!(AssetRegistry::from_json("{}"), Err(AssetError::Parse(_))));
+    assert_eq!(AssetRegistry::from_json("[]"), Err(AssetError::Empty));
+
+    let btc = AssetConfig::new("BTC", "Bitcoin", 8, "bitcoin");
+    assert_eq!(
+        AssetRegistry::new(vec![btc.clone(), btc.clone()]),
+        Err(AssetError::DuplicateSymbol("BTC".into()))
+    );
+
+    let mut lowercase = btc.clone();
+    lowercase.symbol = "btc".into();
+    let mut no_chain = btc.clone();
+    no_chain.chain = " ".into();
+    let mut bad_market = btc.clone();
+    bad_market.oracle_ids.insert("kraken".into(), "XBT/USD?x=1".into());
+    let mut precise = btc;
+    precise.decimals = 40;
+    for config in [lowercase, no_chain, bad_market, precise] {
+        assert!(matches!(AssetRegistry::new(vec![config.clone()]), Err(AssetError::Invalid { .. })), "{:?}", config);
+    }
+}
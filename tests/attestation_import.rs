@@ -0,0 +1,111 @@
+mod common;
+
+use futures::executor::block_on;
+use vext_vault::audit::{read_all, AuditStore, BreakKind, MemoryAuditStore};
+use vext_vault::export::{to_jsonl, Bundle, BundleError};
+use vext_vault::import::{check_file, AttestationFile, ImportError, RecordIssue};
+use vext_vault::keys::DeviceKey;
+use vext_vault::verify::{Policy, VerifyError};
+use vext_vault::IntentAttestation;
+
+use common::{sealed, NOW};
+
+fn bundle(key: &DeviceKey, n: u64) -> Bundle {
+    let store = MemoryAuditStore::new();
+    for i in 1..=n {
+        block_on(store.append(&sealed(key, i))).unwrap();
+    }
+    Bundle::create(block_on(read_all(&store)).unwrap(), None, "https://vault.example", NOW * 1000, key).unwrap()
+}
+
+#[test]
+fn every_export_shape_parses() {
+    let key = DeviceKey::generate();
+    let records: Vec<IntentAttestation> = (1..=3).map(|n| sealed(&key, n)).collect();
+
+    let single = serde_json::to_string(&records[0]).unwrap();
+    assert_eq!(AttestationFile::parse(&single), Ok(AttestationFile::Records(records[..1].to_vec())));
+    let array = serde_json::to_string_pretty(&records).unwrap();
+    assert_eq!(AttestationFile::parse(&array), Ok(AttestationFile::Records(records.clone())));
+
+    let bundle = bundle(&key, 3);
+    let jsonl = to_jsonl(&bundle.records).unwrap();
+    assert_eq!(AttestationFile::parse(&jsonl), Ok(AttestationFile::Records(records.clone())));
+    let parsed = AttestationFile::parse(&serde_json::to_string(&bundle).unwrap()).unwrap();
+    assert_eq!(parsed.attestations(), records.iter().collect::<Vec<_>>());
+
+    assert_eq!(AttestationFile::parse("[]"), Err(ImportError::Empty));
+    let broken = format!("{}{{\"body\":\n", jsonl);
+    assert!(matches!(AttestationFile::parse(&broken), Err(ImportError::Parse(why)) if why.starts_with("line 4:")));
+}
+
+#[test]
+fn clean_bundle_passes_under_trusted_and_embedded_keys() {
+    let key = DeviceKey::generate();
+    let file = AttestationFile::Bundle(Box::new(bundle(&key, 4)));
+    let policy = Policy::new(NOW + 10);
+
+    let report = check_file(&file, Some(&key.verifying_key()), &policy);
+    assert!(report.passed(), "{:?}", report);
+    assert_eq!(report.records.iter().map(|r| r.seq).collect::<Vec<_>>(), [Some(1), Some(2), Some(3), Some(4)]);
+    assert!(report.records.iter().all(|r| r.verified.is_some()));
+    assert!(report.authenticated);
+    let embedded = check_file(&file, None, &policy);
+    assert!(embedded.passed());
+    assert!(!embedded.authenticated);
+}
+
+#[test]
+fn bundle_records_must_be_sealed_by_the_bundle_signer() {
+    let (key, other) = (DeviceKey::generate(), DeviceKey::generate());
+    let store = MemoryAuditStore::new();
+    block_on(store.append(&sealed(&key, 1))).unwrap();
+    block_on(store.append(&sealed(&other, 2))).unwrap();
+    let bundle = Bundle::create(block_on(read_all(&store)).unwrap(), None, "https://vault.example", NOW * 1000, &key).unwrap();
+    let report = check_file(&AttestationFile::Bundle(Box::new(bundle)), None, &Policy::new(NOW + 10));
+
+    // The bundle and each record are self-consistent, but record 2 is foreign.
+    assert_eq!(report.bundle, Some(Ok(())));
+    assert!(report.records[0].passed());
+    assert!(report.records[1].verified.is_some());
+    assert_eq!(report.records[1].issues, [RecordIssue::ForeignSigner { signer: other.public_key_hex() }]);
+}
+
+#[test]
+fn failures_are_reported_per_record() {
+    let key = DeviceKey::generate();
+    let mut records: Vec<IntentAttestation> = (1..=4).map(|n| sealed(&key, n)).collect();
+    records[3] = records[1].clone();
+    records[2].body.hold_duration_ms = 9_999;
+    let report = check_file(&AttestationFile::Records(records), Some(&key.verifying_key()), &Policy::new(NOW + 10));
+
+    assert!(!report.passed());
+    assert_eq!(report.bundle, None);
+    assert_eq!(report.failed_records(), 2);
+    assert!(report.records[0].passed() && report.records[1].passed());
+    assert_eq!(report.records[2].issues, [RecordIssue::Rejected(VerifyError::BadSignature)]);
+    assert_eq!(report.records[3].issues, [RecordIssue::DuplicateNonce { first: 1 }]);
+
+    // Past the TTL, every record says so.
+    let late = check_file(&AttestationFile::Records(vec![sealed(&key, 1)]), None, &Policy::new(NOW + 3_600));
+    assert!(matches!(late.records[0].issues[..], [RecordIssue::Rejected(VerifyError::Expired { .. })]));
+}
+
+#[test]
+fn tampered_bundle_pinpoints_the_broken_links() {
+    let key = DeviceKey::generate();
+    let mut bundle = bundle(&key, 5);
+    // Dropping record 3 leaves a gap in the chain and in the signed digest.
+    bundle.records.remove(2);
+    let report = check_file(&AttestationFile::Bundle(Box::new(bundle)), None, &Policy::new(NOW + 10));
+
+    assert_eq!(report.bundle, Some(Err(BundleError::DigestMismatch)));
+    let failing: Vec<Option<u64>> = report.records.iter().filter(|r| !r.passed()).map(|r| r.seq).collect();
+    assert_eq!(failing, [Some(4)]);
+    match &report.records[2].issues[..] {
+        [RecordIssue::Unlinked(brk)] => assert_eq!(brk.kind, BreakKind::Gap { expected: 3 }),
+        other => panic!("expected a gap, got {:?}", other),
+    }
+    // The chain picks up again after the break.
+    assert!(report.records[3].passed());
+}
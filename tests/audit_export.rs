@@ -0,0 +1,160 @@
+mod common;
+
+use futures::executor::block_on;
+use vext_vault::attestation::{AttestationBody, PriceQuote};
+use vext_vault::audit::{read_all, AuditEntry, AuditStore, BreakKind, MemoryAuditStore};
+use vext_vault::export::{to_csv, to_jsonl, Bundle, BundleError, CSV_HEADER};
+use vext_vault::keys::DeviceKey;
+use vext_vault::merkle::{MerkleError, MerkleTree};
+use vext_vault::verify::verify_inclusion;
+use vext_vault::IntentAttestation;
+
+use common::{body, seal, NOW};
+
+/// BTC records; even ones carry a price quote.
+fn sealed(key: &DeviceKey, n: u64) -> IntentAttestation {
+    let quote = n.is_multiple_of(2).then(|| PriceQuote {
+        source: "median:coinbase,kraken".into(),
+        price: "67012.5".into(),
+        currency: "USD".into(),
+        quoted_at_ms: (NOW + n) * 1000,
+        quote_id: "ab".repeat(32),
+    });
+    seal(key, AttestationBody { asset_symbol: "BTC".into(), quote, ..body(n) })
+}
+
+fn log(key: &DeviceKey, n: u64) -> Vec<AuditEntry> {
+    let store = MemoryAuditStore::new();
+    for i in 1..=n {
+        block_on(store.append(&sealed(key, i))).unwrap();
+    }
+    block_on(read_all(&store)).unwrap()
+}
+
+fn bundle(key: &DeviceKey, n: u64, checkpointed: u64) -> Bundle {
+    let entries = log(key, n);
+    let mut tree = MerkleTree::new();
+    for entry in &entries[..checkpointed as usize] {
+        tree.push(&entry.attestation).unwrap();
+    }
+    let checkpoint = tree.checkpoint("https://vault.example", NOW * 1000).sign(key).unwrap();
+    Bundle::create(entries, Some(checkpoint), "https://vault.example", NOW * 1000, key).unwrap()
+}
+
+#[test]
+fn jsonl_holds_one_full_attestation_per_line() {
+    let key = DeviceKey::generate();
+    let entries = log(&key, 3);
+    let jsonl = to_jsonl(&entries).unwrap();
+    let parsed: Vec<IntentAttestation> = jsonl.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(parsed, entries.iter().map(|e| e.attestation.clone()).collect::<Vec<_>>());
+}
+
+#[test]
+fn csv_rows_follow_the_header_and_quote_when_needed() {
+    let key = DeviceKey::generate();
+    let mut entries = log(&key, 2);
+    entries[0].attestation.body.asset_symbol = "BTC, \"wrapped\"".into();
+    let csv = to_csv(&entries);
+    let rows: Vec<&str> = csv.split("\r\n").collect();
+    assert_eq!(rows[0], CSV_HEADER);
+    assert!(rows[1].starts_with(&format!("1,{},\"BTC, \"\"wrapped\"\"\",,,,", NOW + 1)));
+    assert!(rows[2].starts_with(&format!("2,{},BTC,67012.5,USD,{},", NOW + 2, (NOW + 2) * 1000)));
+    assert_eq!(rows[3], "");
+}
+
+#[test]
+fn csv_cells_never_open_as_formulas() {
+    let key = DeviceKey::generate();
+    let mut entries = log(&key, 1);
+    for (asset, cell) in [
+        ("=HYPERLINK(\"https://evil.example\")", "\"'=HYPERLINK(\"\"https://evil.example\"\")\""),
+        ("+1", "\"'+1\""),
+        ("-2+3", "\"'-2+3\""),
+        ("@SUM(A1)", "\"'@SUM(A1)\""),
+        ("\tTAB", "\"'\tTAB\""),
+        ("BTC-PERP", "BTC-PERP"),
+    ] {
+        entries[0].attestation.body.asset_symbol = asset.into();
+        let csv = to_csv(&entries);
+        let row = csv.split("\r\n").nth(1).unwrap();
+        assert!(row.starts_with(&format!("1,{},{},", NOW + 1, cell)), "{}", row);
+    }
+}
+
+#[test]
+fn signed_bundle_verifies_and_round_trips() {
+    let key = DeviceKey::generate();
+    let bundle = bundle(&key, 5, 4);
+    bundle.verify(&key.verifying_key()).unwrap();
+
+    let json = serde_json::to_string(&bundle).unwrap();
+    let read: Bundle = serde_json::from_str(&json).unwrap();
+    assert_eq!(read, bundle);
+    read.verify(&key.verifying_key()).unwrap();
+
+    let stranger = DeviceKey::generate();
+    assert!(matches!(bundle.verify(&stranger.verifying_key()), Err(BundleError::SignerMismatch { .. })));
+}
+
+#[test]
+fn tampered_bundles_are_rejected() {
+    let key = DeviceKey::generate();
+    let vk = key.verifying_key();
+    let good = bundle(&key, 5, 4);
+
+    let mut edited = good.clone();
+    edited.records[2].attestation.body.hold_duration_ms = 9_999;
+    assert_eq!(edited.verify(&vk), Err(BundleError::DigestMismatch));
+
+    let mut relabelled = good.clone();
+    relabelled.manifest.origin = "https://evil.example".into();
+    assert_eq!(relabelled.verify(&vk), Err(BundleError::BadSignature));
+
+    // Re-signing after dropping an entry still exposes the gap.
+    let mut entries = good.records.clone();
+    entries.remove(1);
+    match Bundle::create(entries, None, "https://vault.example", NOW * 1000, &key) {
+        Err(BundleError::Chain(brk)) => assert_eq!(brk.kind, BreakKind::Gap { expected: 2 }),
+        other => panic!("expected a chain break, got {:?}", other),
+    }
+
+    // A validly signed checkpoint over other records does not match.
+    let mut tree = MerkleTree::new();
+    for entry in &log(&DeviceKey::generate(), 4) {
+        tree.push(&entry.attestation).unwrap();
+    }
+    let other = tree.checkpoint("https://vault.example", NOW * 1000).sign(&key).unwrap();
+    let mismatch = Err(BundleError::CheckpointMismatch { tree_size: 4, records: 5 });
+    let created = Bundle::create(good.records.clone(), Some(other.clone()), "https://vault.example", NOW * 1000, &key);
+    assert_eq!(created.map(|_| ()), mismatch);
+    let mut swapped = good.clone();
+    swapped.manifest.checkpoint = Some(other);
+    swapped.signature = hex::encode(key.sign(&swapped.manifest.signing_message().unwrap()).to_bytes());
+    assert_eq!(swapped.verify(&vk), mismatch);
+}
+
+#[test]
+fn bundle_proves_each_checkpointed_record() {
+    let key = DeviceKey::generate();
+    let vk = key.verifying_key();
+    let good = bundle(&key, 5, 4);
+    let checkpoint = good.manifest.checkpoint.clone().unwrap();
+
+    // One proof per covered record, each usable on its own.
+    assert_eq!(good.proofs.len(), 4);
+    for (proof, entry) in good.proofs.iter().zip(&good.records) {
+        verify_inclusion(&entry.attestation, proof, &checkpoint, &vk).unwrap();
+    }
+    assert!(Bundle::create(good.records.clone(), None, "https://vault.example", NOW * 1000, &key).unwrap().proofs.is_empty());
+
+    let mut dropped = good.clone();
+    dropped.proofs.pop();
+    assert_eq!(dropped.verify(&vk), Err(BundleError::ProofCount { tree_size: 4, proofs: 3 }));
+    let mut swapped = good.clone();
+    swapped.proofs.swap(0, 1);
+    assert_eq!(swapped.verify(&vk), Err(BundleError::MisplacedProof { seq: 1 }));
+    let mut forged = good.clone();
+    forged.proofs[2].audit_path[0] = "00".repeat(32);
+    assert_eq!(forged.verify(&vk), Err(BundleError::Proof { seq: 3, why: MerkleError::RootMismatch }));
+}
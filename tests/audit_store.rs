@@ -0,0 +1,159 @@
+mod common;
+
+use futures::executor::block_on;
+use vext_vault::audit::{
+    upgrade_legacy, verify_chain, verify_store, AuditEntry, AuditError, AuditStore, BreakKind, ChainBreak, ChainHead,
+    MemoryAuditStore, GENESIS_HASH,
+};
+use vext_vault::keys::DeviceKey;
+use vext_vault::verify::{verify_attestation, Policy};
+
+use common::{sealed, NOW};
+
+fn filled(key: &DeviceKey, n: u64) -> MemoryAuditStore {
+    let store = MemoryAuditStore::new();
+    for i in 1..=n {
+        assert_eq!(block_on(store.append(&sealed(key, i))).unwrap().seq, i);
+    }
+    store
+}
+
+#[test]
+fn appended_entries_read_back_intact() {
+    let key = DeviceKey::generate();
+    let store = filled(&key, 3);
+    assert_eq!(block_on(store.count()), Ok(3));
+
+    let page = block_on(store.page(None, 10)).unwrap();
+    let seqs: Vec<u64> = page.entries.iter().map(|e| e.seq).collect();
+    assert_eq!(seqs, [3, 2, 1]);
+    assert_eq!(page.next, None);
+
+    // Round-tripped attestations still verify.
+    let newest = &page.entries[0].attestation;
+    assert_eq!(newest, &sealed(&key, 3));
+    verify_attestation(newest, &key.verifying_key(), &Policy::new(NOW + 3)).unwrap();
+}
+
+#[test]
+fn pages_walk_back_to_the_oldest_entry() {
+    let key = DeviceKey::generate();
+    let store = filled(&key, 7);
+
+    let mut seen = Vec::new();
+    let mut before = None;
+    loop {
+        let page = block_on(store.page(before, 3)).unwrap();
+        assert!(page.entries.len() <= 3);
+        seen.extend(page.entries.iter().map(|e| e.seq));
+        match page.next {
+            Some(next) => before = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(seen, [7, 6, 5, 4, 3, 2, 1]);
+
+    // A full last page does not promise a further one.
+    let page = block_on(store.page(Some(4), 3)).unwrap();
+    assert_eq!((page.entries.len(), page.next), (3, None));
+}
+
+#[test]
+fn empty_and_out_of_range_pages() {
+    let empty = MemoryAuditStore::new();
+    assert_eq!(block_on(empty.count()), Ok(0));
+    assert_eq!(block_on(empty.page(None, 5)).unwrap().entries, vec![]);
+
+    let key = DeviceKey::generate();
+    let store = filled(&key, 2);
+    assert!(block_on(store.page(Some(1), 5)).unwrap().entries.is_empty());
+    assert_eq!(block_on(store.page(Some(99), 5)).unwrap().entries.len(), 2);
+}
+
+/// The whole log, oldest first.
+fn all_entries(store: &MemoryAuditStore) -> Vec<AuditEntry> {
+    let mut entries = block_on(store.page(None, usize::MAX - 1)).unwrap().entries;
+    entries.reverse();
+    entries
+}
+
+#[test]
+fn entries_chain_to_their_predecessor() {
+    let key = DeviceKey::generate();
+    let store = filled(&key, 4);
+    let entries = all_entries(&store);
+    assert_eq!(entries[0].prev_hash, GENESIS_HASH);
+    for pair in entries.windows(2) {
+        assert_eq!(pair[1].prev_hash, pair[0].hash().unwrap());
+    }
+
+    let head = verify_chain(&entries).unwrap();
+    assert_eq!((head.len, head.hash.clone()), (4, entries[3].hash().unwrap()));
+    assert_eq!(block_on(verify_store(&store)), Ok(head.clone()));
+
+    // The next append links to the verified head.
+    let entry = block_on(store.append(&sealed(&key, 5))).unwrap();
+    assert_eq!(head.link(&entry).unwrap().len, 5);
+}
+
+#[test]
+fn pinpoints_the_first_broken_link() {
+    let key = DeviceKey::generate();
+    let entries = all_entries(&filled(&key, 5));
+
+    // Deleting entry 3 leaves a gap there.
+    let mut deleted = entries.clone();
+    deleted.remove(2);
+    assert_eq!(verify_chain(&deleted).unwrap_err(), ChainBreak { seq: 4, kind: BreakKind::Gap { expected: 3 } });
+
+    // Editing entry 2 breaks the link from entry 3.
+    let mut edited = entries.clone();
+    edited[1].attestation.body.asset_symbol = "BTC".into();
+    let err = verify_chain(&edited).unwrap_err();
+    assert_eq!(err.seq, 3);
+    assert!(matches!(err.kind, BreakKind::Mismatch { .. }));
+
+    // Swapping the contents of entries 2 and 4 under their keys breaks at 2.
+    let mut swapped = entries.clone();
+    let (a, b) = (swapped[1].clone(), swapped[3].clone());
+    swapped[1] = AuditEntry { seq: 2, ..b };
+    swapped[3] = AuditEntry { seq: 4, ..a };
+    assert_eq!(verify_chain(&swapped).unwrap_err().seq, 2);
+
+    // Dropping the oldest entry is caught too.
+    assert_eq!(verify_chain(&entries[1..]).unwrap_err().kind, BreakKind::Gap { expected: 1 });
+    assert!(AuditError::BrokenChain(err).to_string().contains("broken at 3"));
+}
+
+#[test]
+fn legacy_log_is_chained_from_genesis() {
+    let key = DeviceKey::generate();
+    // The first layout stored each attestation bare, keyed by sequence number.
+    let stored: Vec<(u64, String)> =
+        (1..=3).map(|n| (n, serde_json::to_string(&sealed(&key, n)).unwrap())).collect();
+
+    let entries = upgrade_legacy(stored).unwrap();
+
+    assert_eq!(entries[0].prev_hash, GENESIS_HASH);
+    assert_eq!(verify_chain(&entries), Ok(ChainHead { len: 3, hash: entries[2].hash().unwrap() }));
+    for (n, entry) in (1..=3).zip(&entries) {
+        assert_eq!(entry.seq, n);
+        assert_eq!(entry.attestation, sealed(&key, n));
+    }
+}
+
+#[test]
+fn upgrade_keeps_entries_already_chained() {
+    let key = DeviceKey::generate();
+    let store = filled(&key, 2);
+    let chained = all_entries(&store);
+    let mut stored: Vec<(u64, String)> =
+        chained.iter().map(|e| (e.seq, serde_json::to_string(e).unwrap())).collect();
+    stored.push((3, serde_json::to_string(&sealed(&key, 3)).unwrap()));
+
+    let entries = upgrade_legacy(stored).unwrap();
+
+    assert_eq!(entries[..2], chained[..]);
+    assert_eq!(entries[2].prev_hash, chained[1].hash().unwrap());
+    assert!(upgrade_legacy(vec![(1, "{}".into())]).is_err());
+}
@@ -0,0 +1,86 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as B64URL, Engine};
+use futures::executor::block_on;
+use p256::ecdsa::{signature::Verifier, DerSignature};
+use sha2::{Digest, Sha256};
+use vext_vault::biometric::{
+    BiometricAssertion, BiometricError, BiometricProvider, CollectedClientData, MockBiometric, FLAG_USER_PRESENT,
+    FLAG_USER_VERIFIED,
+};
+
+const CHALLENGE: &[u8] = b"vext-test-challenge-0123456789ab";
+
+#[test]
+fn same_seed_gives_identical_assertions() {
+    let a = block_on(MockBiometric::from_seed(b"alice").verify_user(CHALLENGE)).unwrap();
+    let b = block_on(MockBiometric::from_seed(b"alice").verify_user(CHALLENGE)).unwrap();
+    let c = block_on(MockBiometric::from_seed(b"bob").verify_user(CHALLENGE)).unwrap();
+
+    assert_eq!(a, b);
+    assert_ne!(a.credential_id, c.credential_id);
+    assert_ne!(a.signature, c.signature);
+}
+
+#[test]
+fn assertion_is_a_well_formed_webauthn_get() {
+    let mock = MockBiometric::from_seed(b"alice").with_origin("vault.vext.com", "https://vault.vext.com");
+    let assertion = block_on(mock.verify_user(CHALLENGE)).unwrap();
+
+    let auth_data = assertion.authenticator_data_bytes().unwrap();
+    assert_eq!(&auth_data[..32], Sha256::digest(b"vault.vext.com").as_slice());
+    assert_eq!(auth_data[32], FLAG_USER_PRESENT | FLAG_USER_VERIFIED);
+    assert_eq!(&auth_data[33..37], &1u32.to_be_bytes());
+    assert!(assertion.user_verified().unwrap());
+
+    let client_data = assertion.client_data_bytes().unwrap();
+    let parsed: CollectedClientData = serde_json::from_slice(&client_data).unwrap();
+    assert_eq!(parsed.kind, "webauthn.get");
+    assert_eq!(parsed.origin, "https://vault.vext.com");
+    assert_eq!(B64URL.decode(&parsed.challenge).unwrap(), CHALLENGE);
+
+    let signed = [auth_data.as_slice(), Sha256::digest(&client_data).as_slice()].concat();
+    let signature = DerSignature::from_bytes(&assertion.signature_bytes().unwrap()).unwrap();
+    mock.verifying_key().verify(&signed, &signature).unwrap();
+}
+
+#[test]
+fn sign_count_increments_per_assertion() {
+    let mock = MockBiometric::from_seed(b"alice");
+    let first = block_on(mock.verify_user(CHALLENGE)).unwrap();
+    let second = block_on(mock.verify_user(CHALLENGE)).unwrap();
+
+    assert_eq!(&first.authenticator_data_bytes().unwrap()[33..37], &1u32.to_be_bytes());
+    assert_eq!(&second.authenticator_data_bytes().unwrap()[33..37], &2u32.to_be_bytes());
+}
+
+#[test]
+fn proof_round_trips_through_biometric_proof() {
+    let assertion = block_on(MockBiometric::from_seed(b"alice").verify_user(CHALLENGE)).unwrap();
+    let proof = assertion.to_proof();
+
+    assert!(proof.starts_with(r#"{"authenticator_data":"#));
+    assert_eq!(BiometricAssertion::from_proof(&proof).unwrap(), assertion);
+    assert!(matches!(BiometricAssertion::from_proof("BIO-ATTESTED"), Err(BiometricError::Malformed(_))));
+}
+
+#[test]
+fn refusals_and_missing_verification_surface() {
+    let cancelled = MockBiometric::from_seed(b"alice").refusing(BiometricError::Cancelled);
+    assert_eq!(block_on(cancelled.verify_user(CHALLENGE)), Err(BiometricError::Cancelled));
+
+    let presence_only = MockBiometric::from_seed(b"alice").with_flags(FLAG_USER_PRESENT);
+    let assertion = block_on(presence_only.verify_user(CHALLENGE)).unwrap();
+    assert!(!assertion.user_verified().unwrap());
+    assert_eq!(assertion.answers(CHALLENGE), Err(BiometricError::NotVerified));
+}
+
+#[test]
+fn assertion_answers_only_its_own_challenge() {
+    let mock = MockBiometric::from_seed(b"alice");
+    let assertion = block_on(mock.verify_user(CHALLENGE)).unwrap();
+    assert_eq!(assertion.answers(CHALLENGE), Ok(()));
+    assert!(matches!(assertion.answers(b"another-session"), Err(BiometricError::Malformed(_))));
+
+    let mut garbled = assertion.clone();
+    garbled.client_data_json = B64URL.encode(b"not json");
+    assert!(matches!(garbled.answers(CHALLENGE), Err(BiometricError::Malformed(_))));
+}
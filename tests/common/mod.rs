@@ -0,0 +1,46 @@
+//! Fixtures shared by the integration tests. Each test crate uses a subset.
+#![allow(dead_code)]
+
+use vext_vault::attestation::{AttestationBody, CURRENT_SCHEMA_VERSION, SIGNING_CONTEXT};
+use vext_vault::keys::DeviceKey;
+use vext_vault::IntentAttestation;
+
+/// Wall clock of the fixtures, in Unix seconds.
+pub const NOW: u64 = 1_760_659_200;
+
+pub const SOLANA_WALLET: &str = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
+
+/// A distinct, well-formed UUIDv4 nonce per `n`.
+pub fn nonce(n: u64) -> String {
+    format!("6f1c2d3e-4b5a-4978-8a9b-{:012x}", n)
+}
+
+/// Unsealed schema 2 body of record `n`: a 1.6 s SOL hold sealed `n` seconds
+/// after `NOW`. Override fields with struct update syntax.
+pub fn body(n: u64) -> AttestationBody {
+    AttestationBody {
+        schema_version: CURRENT_SCHEMA_VERSION,
+        context: SIGNING_CONTEXT.to_string(),
+        asset_symbol: "SOL".into(),
+        quote: None,
+        wallet_pubkey: SOLANA_WALLET.into(),
+        wallet_chain_id: None,
+        biometric_proof: "BIO-ATTESTED".into(),
+        hold_duration_ms: 1600,
+        hold_started_ms: None,
+        hold_ended_ms: None,
+        entropy_hash: "VEXT-HEX-0.5".into(),
+        nonce: nonce(n),
+        timestamp_utc: NOW + n,
+        signer_pubkey: String::new(),
+    }
+}
+
+pub fn seal(key: &DeviceKey, body: AttestationBody) -> IntentAttestation {
+    IntentAttestation::seal(body, key).unwrap()
+}
+
+/// Record `n` sealed by `key` as is.
+pub fn sealed(key: &DeviceKey, n: u64) -> IntentAttestation {
+    seal(key, body(n))
+}
@@ -0,0 +1,47 @@
+use base64::{engine::general_purpose::STANDARD as B64, Engine};
+use vext_vault::keys::{DeviceKey, KeyError, SealedKey, MIN_KDF_ITERATIONS};
+
+const PASSPHRASE: &str = "correct horse battery staple";
+
+// Every seal and open runs the full work factor, so each test does at most two.
+
+#[test]
+fn sealed_key_opens_with_its_passphrase() {
+    let key = DeviceKey::generate();
+    let sealed = key.seal(PASSPHRASE);
+    assert!(sealed.kdf_iterations >= MIN_KDF_ITERATIONS);
+
+    // Survives storage as JSON.
+    let stored: SealedKey = serde_json::from_str(&serde_json::to_string(&sealed).unwrap()).unwrap();
+    let opened = stored.open(PASSPHRASE).unwrap();
+    assert_eq!(opened.public_key_hex(), key.public_key_hex());
+    assert_eq!(opened.sign(b"message"), key.sign(b"message"));
+}
+
+#[test]
+fn wrong_passphrase_is_refused() {
+    let sealed = DeviceKey::generate().seal(PASSPHRASE);
+    assert_eq!(sealed.open("correct horse battery stapler").err(), Some(KeyError::WrongPassphrase));
+}
+
+#[test]
+fn tampered_ciphertext_is_refused() {
+    let mut sealed = DeviceKey::generate().seal(PASSPHRASE);
+    let mut ciphertext = B64.decode(&sealed.ciphertext).unwrap();
+    ciphertext[0] ^= 1;
+    sealed.ciphertext = B64.encode(ciphertext);
+    assert_eq!(sealed.open(PASSPHRASE).err(), Some(KeyError::WrongPassphrase));
+}
+
+#[test]
+fn weakened_work_factor_is_refused_before_derivation() {
+    let weakened = SealedKey {
+        version: 1,
+        kdf_iterations: 1_000,
+        salt: B64.encode([0u8; 16]),
+        nonce: B64.encode([0u8; 12]),
+        ciphertext: B64.encode([0u8; 48]),
+        public_key: DeviceKey::generate().public_key_hex(),
+    };
+    assert!(matches!(weakened.open(PASSPHRASE), Err(KeyError::Corrupted(why)) if why.contains("1000")));
+}
@@ -0,0 +1,186 @@
+mod common;
+
+use k256::ecdsa::SigningKey;
+use serde_json::Value;
+use vext_vault::attestation::AttestationBody;
+use vext_vault::eip712::{self, address_of, recover_address, signing_digest, typed_data, Eip712Error};
+use vext_vault::keys::DeviceKey;
+use vext_vault::verify::{verify_attestation, Policy, VerifyError};
+use vext_vault::IntentAttestation;
+
+use common::{body, seal, NOW};
+
+const KNOWN_KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+const KNOWN_ADDRESS: &str = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+
+fn wallet(seed: u8) -> SigningKey {
+    SigningKey::from_slice(&[seed; 32]).unwrap()
+}
+
+fn address(key: &SigningKey) -> String {
+    format!("0x{}", hex::encode(address_of(key.verifying_key())))
+}
+
+fn sealed(device: &DeviceKey, wallet_address: String, chain_id: Option<u64>) -> IntentAttestation {
+    let body = AttestationBody { asset_symbol: "ETH".into(), wallet_pubkey: wallet_address, wallet_chain_id: chain_id, ..body(0) };
+    seal(device, body)
+}
+
+/// Signs like `eth_signTypedData_v4`: 65 bytes `r || s || v` with `v` in {27, 28}.
+fn sign_typed(body: &AttestationBody, signer: &SigningKey) -> Vec<u8> {
+    let digest = signing_digest(body).unwrap();
+    let (signature, recovery_id) = signer.sign_prehash_recoverable(&digest).unwrap();
+    let mut bytes = signature.to_bytes().to_vec();
+    bytes.push(27 + recovery_id.to_byte());
+    bytes
+}
+
+#[test]
+fn derives_known_address() {
+    let key = SigningKey::from_slice(&hex::decode(KNOWN_KEY).unwrap()).unwrap();
+    assert_eq!(address_of(key.verifying_key()), eip712::parse_address(KNOWN_ADDRESS).unwrap());
+}
+
+#[test]
+fn accepts_typed_data_cosignature() {
+    let device = DeviceKey::generate();
+    let signer = wallet(7);
+    let att = sealed(&device, address(&signer), Some(1));
+    let signature = sign_typed(&att.body, &signer);
+    let att = att.with_wallet_signature(&signature);
+    assert!(att.wallet_signature.as_deref().unwrap().starts_with("0x"));
+
+    let ok = verify_attestation(&att, &device.verifying_key(), &Policy::new(NOW)).unwrap();
+    assert!(ok.wallet_cosigned);
+
+    // Some wallets report v as the raw recovery id.
+    let mut raw_v = signature;
+    raw_v[64] -= 27;
+    let digest = signing_digest(&att.body).unwrap();
+    assert_eq!(recover_address(&digest, &raw_v).unwrap(), address_of(signer.verifying_key()));
+}
+
+#[test]
+fn digest_is_bound_to_chain_and_body() {
+    let device = DeviceKey::generate();
+    let signer = wallet(7);
+    let mainnet = sealed(&device, address(&signer), Some(1)).body;
+
+    let mut other_chain = mainnet.clone();
+    other_chain.wallet_chain_id = Some(137);
+    assert_ne!(signing_digest(&mainnet), signing_digest(&other_chain));
+
+    // Fields outside the displayed struct are still covered through bodyHash.
+    let mut other_entropy = mainnet.clone();
+    other_entropy.entropy_hash = "VEXT-HEX-0.6".into();
+    assert_ne!(signing_digest(&mainnet), signing_digest(&other_entropy));
+}
+
+#[test]
+fn rejects_signature_by_another_wallet() {
+    let device = DeviceKey::generate();
+    let att = sealed(&device, address(&wallet(7)), Some(1));
+    let signature = sign_typed(&att.body, &wallet(8));
+    let att = att.with_wallet_signature(&signature);
+
+    let err = verify_attestation(&att, &device.verifying_key(), &Policy::new(NOW)).unwrap_err();
+    assert_eq!(err, VerifyError::BadWalletSignature);
+}
+
+#[test]
+fn rejects_missing_chain_and_malleable_signatures() {
+    let device = DeviceKey::generate();
+    let signer = wallet(7);
+
+    let att = sealed(&device, address(&signer), None).with_wallet_signature(&[0; 65]);
+    let err = verify_attestation(&att, &device.verifying_key(), &Policy::new(NOW)).unwrap_err();
+    assert_eq!(err, VerifyError::MissingWalletChain);
+
+    let att = sealed(&device, address(&signer), Some(1));
+    let digest = signing_digest(&att.body).unwrap();
+    let (signature, recovery_id) = signer.sign_prehash_recoverable(&digest).unwrap();
+    // (r, n - s) with the flipped recovery id recovers the same key; only low s is accepted.
+    let high_s = k256::ecdsa::Signature::from_scalars(signature.r(), -*signature.s()).unwrap();
+    let mut bytes = high_s.to_bytes().to_vec();
+    bytes.push(27 + (recovery_id.to_byte() ^ 1));
+    assert_eq!(recover_address(&digest, &bytes), Err(Eip712Error::MalformedSignature));
+
+    let att = att.with_wallet_signature(&bytes[..64]);
+    let err = verify_attestation(&att, &device.verifying_key(), &Policy::new(NOW)).unwrap_err();
+    assert_eq!(err, VerifyError::MalformedWalletSignature);
+}
+
+#[test]
+fn accepts_only_standard_recovery_bytes() {
+    let signer = wallet(7);
+    let att = sealed(&DeviceKey::generate(), address(&signer), Some(1));
+    let digest = signing_digest(&att.body).unwrap();
+    let mut bytes = sign_typed(&att.body, &signer);
+    let recovery_id = bytes[64] - 27;
+    let expected = Ok(address_of(signer.verifying_key()));
+
+    assert_eq!(recover_address(&digest, &bytes), expected);
+    bytes[64] = recovery_id;
+    assert_eq!(recover_address(&digest, &bytes), expected);
+    for v in [2, 3, 29, 30, 35] {
+        bytes[64] = v;
+        assert_eq!(recover_address(&digest, &bytes), Err(Eip712Error::MalformedSignature), "v = {}", v);
+    }
+}
+
+#[test]
+fn typed_data_mirrors_the_body() {
+    let device = DeviceKey::generate();
+    let signer = wallet(7);
+    let body = sealed(&device, address(&signer), Some(10)).body;
+    let data = typed_data(&body).unwrap();
+
+    assert_eq!(data["primaryType"], "VextIntent");
+    assert_eq!(data["domain"]["name"], eip712::DOMAIN_NAME);
+    assert_eq!(data["domain"]["chainId"], 10);
+    assert_eq!(data["message"]["asset"], "ETH");
+    assert_eq!(data["message"]["wallet"], address(&signer));
+    assert_eq!(data["message"]["nonce"], body.nonce.as_str());
+    assert_eq!(data["message"]["holdDurationMs"], 1600);
+    let body_hash = eip712::keccak256(&body.signing_message().unwrap());
+    assert_eq!(data["message"]["bodyHash"], format!("0x{}", hex::encode(body_hash)));
+}
+
+/// `eth_signTypedData_v4` hashing of a flat typed-data payload, done from the
+/// JSON alone the way a wallet does it.
+fn hash_typed_data(data: &Value) -> [u8; 32] {
+    let word = |tail: &[u8]| {
+        let mut word = [0u8; 32];
+        word[32 - tail.len()..].copy_from_slice(tail);
+        word
+    };
+    let hash_struct = |name: &str, value: &Value| {
+        let fields = data["types"][name].as_array().unwrap();
+        let members: Vec<String> =
+            fields.iter().map(|f| format!("{} {}", f["type"].as_str().unwrap(), f["name"].as_str().unwrap())).collect();
+        let mut encoded = eip712::keccak256(format!("{}({})", name, members.join(",")).as_bytes()).to_vec();
+        for field in fields {
+            let member = &value[field["name"].as_str().unwrap()];
+            let hex_bytes = || hex::decode(member.as_str().unwrap().strip_prefix("0x").unwrap()).unwrap();
+            encoded.extend(match field["type"].as_str().unwrap() {
+                "string" => eip712::keccak256(member.as_str().unwrap().as_bytes()),
+                "address" | "bytes32" => word(&hex_bytes()),
+                "uint64" | "uint256" => word(&member.as_u64().unwrap().to_be_bytes()),
+                other => panic!("unexpected member type {}", other),
+            });
+        }
+        eip712::keccak256(&encoded)
+    };
+    let domain = hash_struct("EIP712Domain", &data["domain"]);
+    let message = hash_struct(data["primaryType"].as_str().unwrap(), &data["message"]);
+    eip712::keccak256(&[&[0x19, 0x01][..], &domain, &message].concat())
+}
+
+#[test]
+fn typed_data_hashes_to_the_signing_digest() {
+    let device = DeviceKey::generate();
+    for (seed, chain_id) in [(1, 1), (2, 10), (3, 8453)] {
+        let body = sealed(&device, address(&wallet(seed)), Some(chain_id)).body;
+        assert_eq!(hash_typed_data(&typed_data(&body).unwrap()), signing_digest(&body).unwrap(), "chain {}", chain_id);
+    }
+}
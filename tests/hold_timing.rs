@@ -0,0 +1,59 @@
+mod common;
+
+use vext_vault::attestation::AttestationBody;
+use vext_vault::hold::{HoldError, HoldTiming, DEFAULT_MIN_HOLD_MS};
+use vext_vault::keys::DeviceKey;
+use vext_vault::verify::{verify_attestation, Policy, VerifyError};
+
+use common::{body, seal, NOW};
+
+/// Body of record 0 held over `started..ended` (epoch ms), recording `duration_ms`.
+fn held(started: u64, ended: u64, duration_ms: u64) -> AttestationBody {
+    AttestationBody { hold_started_ms: Some(started), hold_ended_ms: Some(ended), hold_duration_ms: duration_ms, ..body(0) }
+}
+
+fn check(key: &DeviceKey, body: AttestationBody, policy: &Policy) -> Result<(), VerifyError> {
+    verify_attestation(&seal(key, body), &key.verifying_key(), policy).map(|_| ())
+}
+
+#[test]
+fn timing_rounds_both_ends_and_enforces_the_minimum() {
+    let timing = HoldTiming::from_epoch_ms(1_000.4, 2_600.6);
+    assert_eq!(timing, HoldTiming { started_ms: 1_000, ended_ms: 2_601 });
+    assert_eq!(timing.duration_ms(), 1_601);
+    assert_eq!(timing.require_min(DEFAULT_MIN_HOLD_MS), Ok(timing));
+    assert_eq!(timing.require_min(2_000), Err(HoldError::TooShort { held_ms: 1_601, min_ms: 2_000 }));
+
+    // A clock reading that runs backwards yields an empty hold, not an underflow.
+    let backwards = HoldTiming::from_epoch_ms(5_000.0, 4_000.0);
+    assert_eq!(backwards.duration_ms(), 0);
+}
+
+#[test]
+fn verifier_checks_signed_hold_endpoints() {
+    let key = DeviceKey::generate();
+    let policy = Policy::new(NOW);
+    let started = NOW * 1000 - 2_000;
+
+    assert_eq!(check(&key, held(started, started + 1_600, 1_600), &policy), Ok(()));
+    assert!(matches!(
+        check(&key, held(started, started + 1_600, 1_700), &policy),
+        Err(VerifyError::InconsistentHold(_))
+    ));
+    let late = NOW * 1000 + (policy.max_clock_skew_secs + 2) * 1000;
+    assert!(matches!(check(&key, held(late - 1_600, late, 1_600), &policy), Err(VerifyError::InconsistentHold(_))));
+    let one_end = AttestationBody { hold_ended_ms: None, ..held(started, started + 1_600, 1_600) };
+    assert!(matches!(check(&key, one_end, &policy), Err(VerifyError::InconsistentHold(_))));
+}
+
+#[test]
+fn verifier_applies_the_configured_minimum() {
+    let key = DeviceKey::generate();
+    let err = check(&key, AttestationBody { hold_duration_ms: 900, ..body(0) }, &Policy::new(NOW)).unwrap_err();
+    assert_eq!(err, VerifyError::HoldTooShort { held_ms: 900, min_ms: DEFAULT_MIN_HOLD_MS });
+
+    let strict = Policy { min_hold_ms: Some(2_000), ..Policy::new(NOW) };
+    assert_eq!(check(&key, body(0), &strict), Err(VerifyError::HoldTooShort { held_ms: 1_600, min_ms: 2_000 }));
+    let lenient = Policy { min_hold_ms: None, ..Policy::new(NOW) };
+    assert_eq!(check(&key, AttestationBody { hold_duration_ms: 10, ..body(0) }, &lenient), Ok(()));
+}
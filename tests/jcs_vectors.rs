@@ -0,0 +1,47 @@
+use serde::Deserialize;
+use vext_vault::jcs;
+
+#[derive(Deserialize)]
+struct Vectors {
+    documents: Vec<Document>,
+    numbers: Vec<NumberVector>,
+}
+
+#[derive(Deserialize)]
+struct Document {
+    name: String,
+    input: String,
+    canonical: String,
+}
+
+#[derive(Deserialize)]
+struct NumberVector {
+    bits: String,
+    canonical: String,
+}
+
+fn vectors() -> Vectors {
+    serde_json::from_str(include_str!("../test-vectors/jcs.json")).expect("vector file parses")
+}
+
+#[test]
+fn documents_match_published_vectors() {
+    for doc in vectors().documents {
+        let value: serde_json::Value = serde_json::from_str(&doc.input).expect(&doc.name);
+        assert_eq!(jcs::canonicalize(&value).unwrap(), doc.canonical, "{}", doc.name);
+    }
+}
+
+#[test]
+fn numbers_match_published_vectors() {
+    for v in vectors().numbers {
+        let f = f64::from_bits(u64::from_str_radix(&v.bits, 16).unwrap());
+        assert_eq!(jcs::format_double(f), v.canonical, "bits {}", v.bits);
+    }
+}
+
+#[test]
+fn rejects_integers_beyond_double_precision() {
+    let value = serde_json::json!({ "n": 9_007_199_254_740_993u64 });
+    assert!(matches!(jcs::canonicalize(&value), Err(jcs::JcsError::IntegerOutOfRange(_))));
+}
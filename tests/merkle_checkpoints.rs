@@ -0,0 +1,153 @@
+mod common;
+
+use vext_vault::audit::{AuditEntry, GENESIS_HASH};
+use vext_vault::keys::DeviceKey;
+use vext_vault::merkle::{leaf_hash, Checkpoint, MerkleError, MerkleTree};
+use vext_vault::verify::{verify_checkpoint, verify_inclusion, VerifyError};
+use vext_vault::IntentAttestation;
+
+use common::{sealed, NOW};
+
+const NOW_MS: u64 = NOW * 1000;
+const ORIGIN: &str = "https://vault.vext.com";
+
+/// RFC 6962 test leaves and the roots of their prefixes.
+const LEAVES: [&str; 8] = ["", "00", "10", "2021", "3031", "40414243", "5051525354555657", "606162636465666768696a6b6c6d6e6f"];
+const ROOTS: [(usize, &str); 4] = [
+    (1, "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d"),
+    (3, "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77"),
+    (7, "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c"),
+    (8, "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328"),
+];
+
+fn batch(key: &DeviceKey, n: u64) -> (MerkleTree, Vec<IntentAttestation>) {
+    let mut tree = MerkleTree::new();
+    let attestations: Vec<_> = (0..n).map(|i| sealed(key, i)).collect();
+    for (i, att) in attestations.iter().enumerate() {
+        assert_eq!(tree.push(att).unwrap(), i as u64);
+    }
+    (tree, attestations)
+}
+
+#[test]
+fn matches_rfc6962_vectors() {
+    assert_eq!(
+        hex::encode(MerkleTree::new().root()),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    for (size, root) in ROOTS {
+        let mut tree = MerkleTree::new();
+        for data in &LEAVES[..size] {
+            tree.push_leaf(leaf_hash(&hex::decode(data).unwrap()));
+        }
+        assert_eq!(hex::encode(tree.root()), root, "size {}", size);
+
+        // Every leaf proves into its root.
+        for index in 0..size as u64 {
+            let proof = tree.inclusion_proof(index).unwrap();
+            let leaf = leaf_hash(&hex::decode(LEAVES[index as usize]).unwrap());
+            proof.verify_leaf(&leaf, &tree.root()).unwrap();
+        }
+    }
+}
+
+#[test]
+fn batch_proofs_match_single_proofs() {
+    for size in 0..=17 {
+        let mut tree = MerkleTree::new();
+        for i in 0..size {
+            tree.push_leaf(leaf_hash(&[i]));
+        }
+        let single: Vec<_> = (0..tree.size()).map(|i| tree.inclusion_proof(i).unwrap()).collect();
+        assert_eq!(tree.inclusion_proofs(), single, "size {}", size);
+    }
+}
+
+#[test]
+fn proves_each_attestation_under_a_signed_checkpoint() {
+    let key = DeviceKey::generate();
+    let (tree, attestations) = batch(&key, 11);
+    let checkpoint = tree.checkpoint(ORIGIN, NOW_MS).sign(&key).unwrap();
+    verify_checkpoint(&checkpoint, &key.verifying_key()).unwrap();
+
+    for (i, att) in attestations.iter().enumerate() {
+        let proof = tree.inclusion_proof(i as u64).unwrap();
+        verify_inclusion(att, &proof, &checkpoint, &key.verifying_key()).unwrap();
+    }
+
+    // Survives a JSON round trip, as it would in an export.
+    let json = serde_json::to_string(&(tree.inclusion_proof(4).unwrap(), &checkpoint)).unwrap();
+    let (proof, checkpoint) = serde_json::from_str(&json).unwrap();
+    verify_inclusion(&attestations[4], &proof, &checkpoint, &key.verifying_key()).unwrap();
+}
+
+#[test]
+fn rejects_wrong_leaves_proofs_and_checkpoints() {
+    let key = DeviceKey::generate();
+    let (tree, attestations) = batch(&key, 6);
+    let checkpoint = tree.checkpoint(ORIGIN, NOW_MS).sign(&key).unwrap();
+    let proof = tree.inclusion_proof(2).unwrap();
+    let trusted = key.verifying_key();
+
+    // Another attestation, or this one edited, is not at leaf 2.
+    assert_eq!(
+        verify_inclusion(&attestations[3], &proof, &checkpoint, &trusted),
+        Err(VerifyError::NotIncluded(MerkleError::RootMismatch))
+    );
+    let mut edited = attestations[2].clone();
+    edited.body.hold_duration_ms += 1;
+    assert!(verify_inclusion(&edited, &proof, &checkpoint, &trusted).is_err());
+
+    // Proofs must match the checkpoint's size and have the right shape.
+    let (bigger, _) = batch(&key, 7);
+    assert_eq!(
+        verify_inclusion(&attestations[2], &bigger.inclusion_proof(2).unwrap(), &checkpoint, &trusted),
+        Err(VerifyError::ProofSizeMismatch { proof_size: 7, tree_size: 6 })
+    );
+    let mut short = proof.clone();
+    short.audit_path.pop();
+    assert_eq!(
+        verify_inclusion(&attestations[2], &short, &checkpoint, &trusted),
+        Err(VerifyError::NotIncluded(MerkleError::WrongPathLength))
+    );
+    assert_eq!(tree.inclusion_proof(6), Err(MerkleError::IndexOutOfRange { index: 6, size: 6 }));
+
+    // A forged root, or a checkpoint from another key, is refused.
+    let mut forged = checkpoint.clone();
+    forged.checkpoint.root_hash = hex::encode(bigger.root());
+    assert_eq!(verify_checkpoint(&forged, &trusted), Err(VerifyError::BadCheckpointSignature));
+    let other = DeviceKey::generate();
+    let foreign = tree.checkpoint(ORIGIN, NOW_MS).sign(&other).unwrap();
+    assert!(matches!(verify_checkpoint(&foreign, &trusted), Err(VerifyError::SignerMismatch { .. })));
+}
+
+#[test]
+fn sync_keeps_leaf_index_at_seq_minus_one() {
+    let key = DeviceKey::generate();
+    let (expected, attestations) = batch(&key, 5);
+    // Only the leaf mapping matters here, not the hash chain.
+    let entries: Vec<AuditEntry> = attestations
+        .into_iter()
+        .enumerate()
+        .map(|(i, attestation)| AuditEntry { seq: i as u64 + 1, prev_hash: GENESIS_HASH.into(), attestation })
+        .collect();
+
+    // An append seen before the rebuild leaves a gap and is not pushed.
+    let mut tree = MerkleTree::new();
+    assert_eq!(tree.sync(&entries[4..]).unwrap(), 0);
+    assert_eq!(tree.sync(&entries[..3]).unwrap(), 3);
+    // Overlapping and repeated entries are skipped.
+    assert_eq!(tree.sync(&entries[1..]).unwrap(), 5);
+    assert_eq!(tree.sync(&entries).unwrap(), 5);
+    assert_eq!(tree, expected);
+}
+
+#[test]
+fn checkpoints_are_due_when_the_tree_grew_and_time_passed() {
+    let last = Checkpoint { origin: ORIGIN.into(), tree_size: 4, root_hash: String::new(), timestamp_ms: NOW_MS };
+    assert!(!Checkpoint::due(None, 0, NOW_MS, 1_000));
+    assert!(Checkpoint::due(None, 1, NOW_MS, 1_000));
+    assert!(!Checkpoint::due(Some(&last), 4, NOW_MS + 5_000, 1_000));
+    assert!(!Checkpoint::due(Some(&last), 5, NOW_MS + 999, 1_000));
+    assert!(Checkpoint::due(Some(&last), 5, NOW_MS + 1_000, 1_000));
+}
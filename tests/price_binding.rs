@@ -0,0 +1,120 @@
+mod common;
+
+use sha2::{Digest, Sha256};
+use vext_vault::attestation::{AttestationBody, PriceQuote};
+use vext_vault::keys::DeviceKey;
+use vext_vault::oracle::{aggregate, OraclePolicy, Pair, Quote};
+use vext_vault::verify::{verify_attestation, Policy, VerifyError};
+use vext_vault::IntentAttestation;
+
+use common::{body, seal, NOW};
+
+fn quote(source: &str, price: &str, timestamp_ms: u64) -> Quote {
+    Quote { source: source.into(), symbol: "SOL".into(), price: price.into(), timestamp_ms }
+}
+
+fn bound(price: &str, quoted_at_ms: u64) -> PriceQuote {
+    PriceQuote {
+        source: "median:coinbase,kraken".into(),
+        price: price.into(),
+        currency: "USD".into(),
+        quoted_at_ms,
+        quote_id: "00".repeat(32),
+    }
+}
+
+fn sealed(device: &DeviceKey, quote: Option<PriceQuote>) -> IntentAttestation {
+    seal(device, AttestationBody { quote, ..body(0) })
+}
+
+fn policy(max_quote_age_ms: u64) -> Policy {
+    Policy { max_quote_age_ms: Some(max_quote_age_ms), ..Policy::new(NOW) }
+}
+
+#[test]
+fn binds_the_aggregated_price_and_its_inputs() {
+    let quotes = [quote("coinbase", "150.12", NOW * 1000 - 4_000), quote("kraken", "150.20", NOW * 1000 - 2_000)];
+    let results = quotes.iter().map(|q| (q.source.clone(), Ok(q.clone()))).collect();
+    let price = aggregate(&Pair::usd("SOL"), results, NOW * 1000, &OraclePolicy::default()).unwrap();
+
+    let binding = price.bind().unwrap();
+    assert_eq!(binding.source, "median:coinbase,kraken");
+    assert_eq!(binding.price, "150.16");
+    assert_eq!(binding.currency, "USD");
+    assert_eq!(binding.quoted_at_ms, NOW * 1000 - 4_000);
+
+    let canonical = r#"[{"price":"150.12","source":"coinbase","symbol":"SOL","timestamp_ms":1760659196000},{"price":"150.20","source":"kraken","symbol":"SOL","timestamp_ms":1760659198000}]"#;
+    assert_eq!(binding.quote_id, hex::encode(Sha256::digest(canonical)));
+}
+
+#[test]
+fn accepts_a_fresh_quote_and_reports_its_age() {
+    let device = DeviceKey::generate();
+    let att = sealed(&device, Some(bound("150.16", NOW * 1000 - 4_000)));
+
+    let ok = verify_attestation(&att, &device.verifying_key(), &policy(10_000)).unwrap();
+    assert_eq!(ok.quote_age_ms, Some(4_000));
+
+    let err = verify_attestation(&att, &device.verifying_key(), &policy(3_000)).unwrap_err();
+    assert_eq!(err, VerifyError::QuoteTooOld { age_ms: 4_000, max_age_ms: 3_000 });
+}
+
+#[test]
+fn missing_quote_only_fails_when_age_is_bounded() {
+    let device = DeviceKey::generate();
+    let att = sealed(&device, None);
+    assert!(!serde_json::to_string(&att).unwrap().contains("quote"));
+
+    let ok = verify_attestation(&att, &device.verifying_key(), &Policy::new(NOW)).unwrap();
+    assert_eq!(ok.quote_age_ms, None);
+
+    let err = verify_attestation(&att, &device.verifying_key(), &policy(10_000)).unwrap_err();
+    assert_eq!(err, VerifyError::MissingQuote);
+}
+
+#[test]
+fn rejects_malformed_and_postdated_quotes() {
+    let device = DeviceKey::generate();
+    for price in ["1e5", "-3", "0.00", "", "12.", " 12"] {
+        let att = sealed(&device, Some(bound(price, NOW * 1000)));
+        let err = verify_attestation(&att, &device.verifying_key(), &Policy::new(NOW)).unwrap_err();
+        assert_eq!(err, VerifyError::MalformedQuote(price.into()));
+    }
+
+    let late = (NOW + 60) * 1000;
+    let att = sealed(&device, Some(bound("150.16", late)));
+    let err = verify_attestation(&att, &device.verifying_key(), &Policy::new(NOW)).unwrap_err();
+    assert_eq!(err, VerifyError::QuoteAfterSeal { quoted_at_ms: late, timestamp_utc: NOW });
+}
+
+#[test]
+fn quoted_price_is_covered_by_the_seal() {
+    let device = DeviceKey::generate();
+    let mut att = sealed(&device, Some(bound("150.16", NOW * 1000)));
+    att.body.quote.as_mut().unwrap().price = "15.016".into();
+
+    let err = verify_attestation(&att, &device.verifying_key(), &Policy::new(NOW)).unwrap_err();
+    assert_eq!(err, VerifyError::BadSignature);
+}
+
+#[test]
+fn quote_injected_into_a_legacy_record_is_not_trusted() {
+    let device = DeviceKey::generate();
+    // Schema 1 signs only the legacy field set, so a quote added later still
+    // leaves the device signature valid.
+    let genuine = seal(&device, AttestationBody { schema_version: 1, ..body(0) });
+    let mut forged = genuine.clone();
+    forged.body.quote = Some(bound("150.16", NOW * 1000));
+    let legacy = |policy: Policy| Policy { allow_legacy_schema: true, ..policy };
+
+    let err = verify_attestation(&forged, &device.verifying_key(), &legacy(policy(10_000))).unwrap_err();
+    assert_eq!(err, VerifyError::UnsignedField("quote"));
+    let json = serde_json::to_string(&forged).unwrap();
+    assert!(serde_json::from_str::<IntentAttestation>(&json).is_err());
+
+    // A genuine legacy record has no quote to satisfy a quote policy with.
+    let err = verify_attestation(&genuine, &device.verifying_key(), &legacy(policy(10_000))).unwrap_err();
+    assert_eq!(err, VerifyError::MissingQuote);
+    let ok = verify_attestation(&genuine, &device.verifying_key(), &legacy(Policy::new(NOW))).unwrap();
+    assert_eq!(ok.quote_age_ms, None);
+}
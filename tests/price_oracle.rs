@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use futures::executor::block_on;
+use futures::future::{ready, LocalBoxFuture};
+use vext_vault::oracle::{
+    aggregate, BinanceOracle, CoinbaseOracle, HttpGet, KrakenOracle, OracleError, OraclePolicy, OracleSet,
+    Pair, PriceOracle, Quote, Rejection,
+};
+
+const NOW_MS: u64 = 1_760_659_200_000;
+
+/// A local exchange: answers each path with a canned status and body, recording requests.
+struct MockExchange {
+    base_url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+fn serve(routes: &[(&str, u16, &str)]) -> MockExchange {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let routes: HashMap<String, (u16, String)> =
+        routes.iter().map(|(path, status, body)| (path.to_string(), (*status, body.to_string()))).collect();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let log = requests.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request_line = String::new();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            reader.read_line(&mut request_line).unwrap();
+            // Drain the headers; GET requests carry no body.
+            let mut header = String::new();
+            while reader.read_line(&mut header).unwrap() > 2 {
+                header.clear();
+            }
+            let path = request_line.split_whitespace().nth(1).unwrap_or("/").to_string();
+            log.lock().unwrap().push(path.clone());
+            let (status, body) = routes.get(&path).cloned().unwrap_or((404, "{}".into()));
+            let response = format!(
+                "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    MockExchange { base_url, requests }
+}
+
+/// Blocking plain-HTTP client; enough to talk to `MockExchange`.
+struct StdHttp;
+
+impl HttpGet for StdHttp {
+    fn get<'a>(&'a self, url: &'a str) -> LocalBoxFuture<'a, Result<String, OracleError>> {
+        Box::pin(ready(fetch(url)))
+    }
+}
+
+fn fetch(url: &str) -> Result<String, OracleError> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| OracleError::Http("not http".into()))?;
+    let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let mut stream = TcpStream::connect(host).map_err(|e| OracleError::Http(e.to_string()))?;
+    write!(stream, "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n", path, host)
+        .map_err(|e| OracleError::Http(e.to_string()))?;
+    let mut raw = String::new();
+    stream.read_to_string(&mut raw).map_err(|e| OracleError::Http(e.to_string()))?;
+    let (head, body) = raw.split_once("\r\n\r\n").ok_or_else(|| OracleError::Http("truncated".into()))?;
+    let status: u16 = head.split_whitespace().nth(1).and_then(|s| s.parse().ok()).unwrap_or(0);
+    if !(200..300).contains(&status) {
+        return Err(OracleError::Status(status));
+    }
+    Ok(body.to_string())
+}
+
+fn quote(source: &str, price: &str, timestamp_ms: u64) -> (String, Result<Quote, OracleError>) {
+    let quote = Quote { source: source.into(), symbol: "BTC".into(), price: price.into(), timestamp_ms };
+    (source.into(), Ok(quote))
+}
+
+#[test]
+fn adapters_read_each_exchange_format() {
+    let exchange = serve(&[
+        ("/v2/prices/BTC-USD/spot", 200, r#"{"data":{"amount":"67012.50","base":"BTC","currency":"USD"}}"#),
+        ("/0/public/Ticker?pair=XBTUSD", 200, r#"{"error":[],"result":{"XXBTZUSD":{"c":["67010.1","0.01"]}}}"#),
+        (
+            "/api/v3/ticker/24hr?symbol=BTCUSDT",
+            200,
+            r#"{"symbol":"BTCUSDT","lastPrice":"67015.00000000","closeTime":1760659199000}"#,
+        ),
+    ]);
+    let http: Rc<dyn HttpGet> = Rc::new(StdHttp);
+
+    let coinbase = block_on(CoinbaseOracle::with_base_url(http.clone(), &exchange.base_url).quote(&Pair::usd("BTC"), NOW_MS)).unwrap();
+    assert_eq!((coinbase.source.as_str(), coinbase.price.as_str(), coinbase.timestamp_ms), ("coinbase", "67012.50", NOW_MS));
+
+    let kraken = block_on(KrakenOracle::with_base_url(http.clone(), &exchange.base_url).quote(&Pair::usd("BTC"), NOW_MS)).unwrap();
+    assert_eq!(kraken.price, "67010.1");
+
+    let binance = BinanceOracle::with_base_url(http, &exchange.base_url);
+    assert_eq!(
+        block_on(binance.quote(&Pair::usd("BTC"), NOW_MS)),
+        Err(OracleError::NoMarket { symbol: "BTC".into(), currency: "USD".into() })
+    );
+    let binance = block_on(binance.quote(&Pair::new("BTC", "USDT"), NOW_MS)).unwrap();
+    assert_eq!((binance.price.as_str(), binance.timestamp_ms), ("67015.00000000", 1_760_659_199_000));
+
+    assert_eq!(exchange.requests.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn failing_sources_are_reported_not_fatal() {
+    let exchange = serve(&[
+        ("/v2/prices/SOL-EUR/spot", 503, "upstream down"),
+        ("/0/public/Ticker?pair=SOLEUR", 200, r#"{"error":[],"result":{"SOLEUR":{"c":["150.12","3"]}}}"#),
+        ("/api/v3/ticker/24hr?symbol=SOLEUR", 200, r#"{"symbol":"SOLEUR","lastPrice":"150.20","closeTime":1760659199500}"#),
+    ]);
+    let http: Rc<dyn HttpGet> = Rc::new(StdHttp);
+    let oracles: Vec<Rc<dyn PriceOracle>> = vec![
+        Rc::new(CoinbaseOracle::with_base_url(http.clone(), &exchange.base_url)),
+        Rc::new(KrakenOracle::with_base_url(http.clone(), &exchange.base_url)),
+        Rc::new(BinanceOracle::with_base_url(http, &exchange.base_url)),
+    ];
+    let mut set = OracleSet::new(oracles, OraclePolicy::default());
+
+    let price = block_on(set.price(&Pair::new("SOL", "EUR"), NOW_MS)).unwrap();
+    assert_eq!(price.price, "150.16");
+    assert_eq!(price.as_of_ms, 1_760_659_199_500);
+    assert_eq!(price.rejected, vec![("coinbase".to_string(), Rejection::Failed(OracleError::Status(503)))]);
+
+    set.policy.min_sources = 3;
+    assert_eq!(
+        block_on(set.price(&Pair::new("SOL", "EUR"), NOW_MS)),
+        Err(OracleError::InsufficientSources { usable: 2, required: 3 })
+    );
+}
+
+#[test]
+fn binance_sits_out_usd_pairs() {
+    let exchange = serve(&[
+        ("/v2/prices/SOL-USD/spot", 200, r#"{"data":{"amount":"150.10"}}"#),
+        ("/0/public/Ticker?pair=SOLUSD", 200, r#"{"error":[],"result":{"SOLUSD":{"c":["150.12","3"]}}}"#),
+        ("/api/v3/ticker/24hr?symbol=SOLUSDT", 200, r#"{"symbol":"SOLUSDT","lastPrice":"151.90","closeTime":1760659199500}"#),
+    ]);
+    let http: Rc<dyn HttpGet> = Rc::new(StdHttp);
+    let oracles: Vec<Rc<dyn PriceOracle>> = vec![
+        Rc::new(CoinbaseOracle::with_base_url(http.clone(), &exchange.base_url)),
+        Rc::new(KrakenOracle::with_base_url(http.clone(), &exchange.base_url)),
+        Rc::new(BinanceOracle::with_base_url(http, &exchange.base_url)),
+    ];
+    let set = OracleSet::new(oracles, OraclePolicy::default());
+
+    let price = block_on(set.price(&Pair::usd("SOL"), NOW_MS)).unwrap();
+    assert_eq!(price.price, "150.11");
+    let sources: Vec<&str> = price.quotes.iter().map(|q| q.source.as_str()).collect();
+    assert_eq!(sources, ["coinbase", "kraken"]);
+    assert!(price.rejected.is_empty());
+    assert_eq!(exchange.requests.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn rejects_unreadable_payloads() {
+    let exchange = serve(&[
+        ("/v2/prices/ETH-USD/spot", 200, r#"{"data":{"amount":"-1"}}"#),
+        ("/0/public/Ticker?pair=ETHUSD", 200, r#"{"error":["EQuery:Unknown asset pair"]}"#),
+        ("/api/v3/ticker/24hr?symbol=ETHUSDT", 200, "<html>"),
+    ]);
+    let http: Rc<dyn HttpGet> = Rc::new(StdHttp);
+    let results = [
+        block_on(CoinbaseOracle::with_base_url(http.clone(), &exchange.base_url).quote(&Pair::usd("ETH"), NOW_MS)),
+        block_on(KrakenOracle::with_base_url(http.clone(), &exchange.base_url).quote(&Pair::usd("ETH"), NOW_MS)),
+        block_on(BinanceOracle::with_base_url(http, &exchange.base_url).quote(&Pair::new("ETH", "USDT"), NOW_MS)),
+    ];
+    for result in results {
+        assert!(matches!(result, Err(OracleError::Malformed(_))), "{:?}", result);
+    }
+}
+
+#[test]
+fn drops_outliers_beyond_the_deviation_threshold() {
+    let results = vec![
+        quote("coinbase", "100.00", NOW_MS),
+        quote("kraken", "100.50", NOW_MS),
+        quote("binance", "110.00", NOW_MS), // ~945 bps above the median
+    ];
+    let price = aggregate(&Pair::usd("BTC"), results, NOW_MS, &OraclePolicy::default()).unwrap();
+    assert_eq!(price.price, "100.25");
+    assert_eq!(price.quotes.len(), 2);
+    assert_eq!(price.rejected, vec![("binance".to_string(), Rejection::Deviates { bps: 945 })]);
+}
+
+#[test]
+fn stale_quotes_do_not_count_and_prices_age() {
+    let policy = OraclePolicy::default();
+    let old = NOW_MS - policy.max_age_ms - 1;
+    let results = vec![quote("coinbase", "100", NOW_MS - 5_000), quote("kraken", "101", NOW_MS), quote("binance", "99", old)];
+
+    let price = aggregate(&Pair::usd("BTC"), results, NOW_MS, &policy).unwrap();
+    assert_eq!(price.price, "100.5");
+    assert_eq!(price.rejected, vec![("binance".to_string(), Rejection::Stale { age_ms: policy.max_age_ms + 1 })]);
+
+    // The price is as old as its oldest input.
+    assert_eq!(price.age_ms(NOW_MS), 5_000);
+    assert!(!price.is_stale(NOW_MS, policy.max_age_ms));
+    assert!(price.is_stale(NOW_MS + policy.max_age_ms, policy.max_age_ms));
+
+    let only_stale = vec![quote("coinbase", "100", old), quote("kraken", "100", old)];
+    assert_eq!(
+        aggregate(&Pair::usd("BTC"), only_stale, NOW_MS, &policy),
+        Err(OracleError::InsufficientSources { usable: 0, required: 2 })
+    );
+}
@@ -0,0 +1,99 @@
+use vext_vault::oracle::{
+    aggregate, coinbase_subscribe_message, parse_coinbase_ticker, parse_rfc3339_ms, OraclePolicy, Pair,
+    Quote,
+    RefreshSchedule,
+};
+
+const NOW_MS: u64 = 1_760_659_200_000; // 2025-10-17T00:00:00Z
+
+#[test]
+fn backs_off_exponentially_and_recovers() {
+    let mut schedule = RefreshSchedule::new(15_000, 120_000);
+    assert_eq!(schedule.next_delay_ms(true), 15_000);
+
+    let delays: Vec<u64> = (0..5).map(|_| schedule.next_delay_ms(false)).collect();
+    assert_eq!(delays, [30_000, 60_000, 120_000, 120_000, 120_000]);
+    assert_eq!(schedule.failures(), 5);
+
+    assert_eq!(schedule.next_delay_ms(true), 15_000);
+    assert_eq!(schedule.failures(), 0);
+
+    // Many failures in a row saturate instead of overflowing.
+    let mut schedule = RefreshSchedule::new(15_000, 120_000);
+    for _ in 0..100 {
+        assert!(schedule.next_delay_ms(false) <= 120_000);
+    }
+}
+
+#[test]
+fn parses_coinbase_ticker_messages() {
+    let pairs = [Pair::usd("ETH"), Pair::usd("SOL")];
+    let tick = r#"{"type":"ticker","sequence":1,"product_id":"ETH-USD","price":"2412.35","time":"2025-10-17T00:00:01.250431Z"}"#;
+    let quote = parse_coinbase_ticker(tick, &pairs, NOW_MS).unwrap();
+    assert_eq!(
+        quote,
+        Quote { source: "coinbase".into(), symbol: "ETH".into(), price: "2412.35".into(), timestamp_ms: NOW_MS + 1_250 }
+    );
+
+    let untimed = r#"{"type":"ticker","product_id":"SOL-USD","price":"150.1"}"#;
+    assert_eq!(parse_coinbase_ticker(untimed, &pairs, NOW_MS).unwrap().timestamp_ms, NOW_MS);
+
+    for ignored in [
+        r#"{"type":"subscriptions","channels":[{"name":"ticker","product_ids":["ETH-USD"]}]}"#,
+        r#"{"type":"heartbeat","product_id":"ETH-USD","sequence":1}"#,
+        r#"{"type":"ticker","product_id":"ETH-EUR","price":"2200.00"}"#,
+        r#"{"type":"ticker","product_id":"BTC-USD","price":"67000.00"}"#,
+        r#"{"type":"ticker","product_id":"ETH-USD","price":"NaN"}"#,
+        "not json",
+    ] {
+        assert_eq!(parse_coinbase_ticker(ignored, &pairs, NOW_MS), None, "{}", ignored);
+    }
+
+    let subscribe: serde_json::Value = serde_json::from_str(&coinbase_subscribe_message(&[Pair::usd("BTC"), Pair::usd("ETH")])).unwrap();
+    assert_eq!(subscribe["product_ids"], serde_json::json!(["BTC-USD", "ETH-USD"]));
+    assert_eq!(subscribe["channels"], serde_json::json!(["ticker"]));
+}
+
+#[test]
+fn parses_utc_timestamps() {
+    assert_eq!(parse_rfc3339_ms("1970-01-01T00:00:00Z"), Some(0));
+    assert_eq!(parse_rfc3339_ms("2025-10-17T00:00:00Z"), Some(NOW_MS));
+    assert_eq!(parse_rfc3339_ms("2024-02-29T12:30:15.5Z"), Some(1_709_209_815_500));
+    for bad in ["2025-10-17T00:00:00", "2025-13-01T00:00:00Z", "2025-10-17 00:00:00Z", "2025-10-17T00:00:00.x1Z"] {
+        assert_eq!(parse_rfc3339_ms(bad), None, "{}", bad);
+    }
+
+    // Out-of-range years are refused rather than wrapping or panicking.
+    assert_eq!(parse_rfc3339_ms("9999-12-31T23:59:59.999Z"), Some(253_402_300_799_999));
+    for bad in ["0000-01-01T00:00:00Z", "1969-12-31T23:59:59Z", "18446744073709551615-12-31T23:59:59Z", "50000000000000-06-01T00:00:00Z"] {
+        assert_eq!(parse_rfc3339_ms(bad), None, "{}", bad);
+    }
+
+    // Days past the end of the month are refused, with Gregorian leap years.
+    assert_eq!(parse_rfc3339_ms("2000-02-29T00:00:00Z"), Some(951_782_400_000));
+    for bad in ["2025-02-30T00:00:00Z", "2025-04-31T00:00:00Z", "2025-02-29T00:00:00Z", "2100-02-29T00:00:00Z"] {
+        assert_eq!(parse_rfc3339_ms(bad), None, "{}", bad);
+    }
+}
+
+#[test]
+fn streamed_tick_replaces_its_source() {
+    let quote = |source: &str, price: &str, timestamp_ms| Quote {
+        source: source.into(),
+        symbol: "BTC".into(),
+        price: price.into(),
+        timestamp_ms,
+    };
+    let polled = vec![
+        ("coinbase".to_string(), Ok(quote("coinbase", "100.00", NOW_MS - 10_000))),
+        ("kraken".to_string(), Ok(quote("kraken", "100.40", NOW_MS - 10_000))),
+    ];
+    let policy = OraclePolicy::default();
+    let price = aggregate(&Pair::usd("BTC"), polled, NOW_MS, &policy).unwrap();
+    assert_eq!(price.price, "100.20");
+
+    let ticked = price.with_quote(quote("coinbase", "100.60", NOW_MS), NOW_MS, &policy).unwrap();
+    assert_eq!(ticked.price, "100.50");
+    assert_eq!(ticked.quotes.len(), 2);
+    assert_eq!(ticked.as_of_ms, NOW_MS - 10_000);
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use serde_json::json;
+use vext_vault::attestation::AttestationBody;
+use vext_vault::keys::DeviceKey;
+use vext_vault::verify::{verify_attestation, Policy, VerifyError};
+use vext_vault::IntentAttestation;
+
+use common::{body, seal, NOW};
+
+/// A genuine schema 1 seal: only the legacy field set is signed.
+fn legacy(key: &DeviceKey) -> IntentAttestation {
+    seal(key, AttestationBody { schema_version: 1, ..body(0) })
+}
+
+/// Legacy records verify only when the auditor asks for them.
+fn allowing_legacy() -> Policy {
+    Policy { allow_legacy_schema: true, ..Policy::new(NOW) }
+}
+
+/// The flat layout schema 1 wrote, before the envelope existed.
+fn flat(att: &IntentAttestation) -> serde_json::Value {
+    let body = &att.body;
+    json!({
+        "asset_symbol": body.asset_symbol,
+        "wallet_pubkey": body.wallet_pubkey,
+        "biometric_proof": body.biometric_proof,
+        "hold_duration_ms": body.hold_duration_ms,
+        "entropy_hash": body.entropy_hash,
+        "nonce": body.nonce,
+        "timestamp_utc": body.timestamp_utc,
+        "signature": att.signature,
+        "signer_pubkey": body.signer_pubkey,
+    })
+}
+
+#[test]
+fn flat_v1_records_upgrade_and_verify() {
+    let key = DeviceKey::generate();
+    let att = legacy(&key);
+    let upgraded: IntentAttestation = serde_json::from_value(flat(&att)).unwrap();
+    assert_eq!(upgraded, att);
+    assert_eq!(verify_attestation(&upgraded, &key.verifying_key(), &allowing_legacy()).unwrap().schema_version, 1);
+
+    // An upgraded record round-trips through its envelope form.
+    let enveloped: IntentAttestation = serde_json::from_str(&serde_json::to_string(&att).unwrap()).unwrap();
+    assert_eq!(enveloped, att);
+}
+
+#[test]
+fn v1_envelopes_with_v2_only_fields_are_refused() {
+    let key = DeviceKey::generate();
+    let genuine = serde_json::to_value(legacy(&key)).unwrap();
+    let decorations = [
+        ("body", "quote", json!({"source": "x", "price": "1", "currency": "USD", "quoted_at_ms": 0, "quote_id": "00"})),
+        ("body", "wallet_chain_id", json!(1)),
+        ("body", "hold_started_ms", json!(NOW * 1000 - 5_000)),
+        ("body", "hold_ended_ms", json!(NOW * 1000)),
+        ("", "wallet_signature", json!("3yZe7d")),
+    ];
+    for (parent, field, value) in decorations {
+        let mut decorated = genuine.clone();
+        let target = if parent.is_empty() { &mut decorated } else { &mut decorated[parent] };
+        target[field] = value;
+        let err = serde_json::from_value::<IntentAttestation>(decorated).unwrap_err();
+        assert!(err.to_string().contains(field), "{}: {}", field, err);
+    }
+}
+
+#[test]
+fn verifier_refuses_decorated_or_unwanted_legacy_records() {
+    let key = DeviceKey::generate();
+    let mut decorated = legacy(&key);
+    decorated.body.hold_started_ms = Some(NOW * 1000 - 5_000);
+    decorated.body.hold_ended_ms = Some(NOW * 1000 - 3_400);
+    let err = verify_attestation(&decorated, &key.verifying_key(), &allowing_legacy()).unwrap_err();
+    assert_eq!(err, VerifyError::UnsignedField("hold_started_ms"));
+
+    // Refused by default.
+    let err = verify_attestation(&legacy(&key), &key.verifying_key(), &Policy::new(NOW)).unwrap_err();
+    assert_eq!(err, VerifyError::LegacySchema);
+}
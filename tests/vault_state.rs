@@ -0,0 +1,190 @@
+use vext_vault::state::{InvalidTransition, VaultEvent, VaultState};
+
+const WALLET: &str = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
+const OTHER_WALLET: &str = "7EcDhSYGxXyscszYEp35KHN8vvw3svAuLKTzXwCFLtV";
+
+fn wallet() -> String {
+    WALLET.to_string()
+}
+
+fn other_wallet() -> String {
+    OTHER_WALLET.to_string()
+}
+
+/// The states in which a wallet is linked.
+fn linked_states() -> Vec<VaultState> {
+    all_states().into_iter().filter(VaultState::wallet_linked).collect()
+}
+
+/// One representative of every state shape the flow can be in.
+fn all_states() -> Vec<VaultState> {
+    vec![
+        VaultState::KeySealed { unsealing: false },
+        VaultState::KeySealed { unsealing: true },
+        VaultState::AwaitingWallet,
+        VaultState::AwaitingBiometric { wallet: wallet(), scanning: false },
+        VaultState::AwaitingBiometric { wallet: wallet(), scanning: true },
+        VaultState::Locked { wallet: wallet() },
+        VaultState::Unlocked { wallet: wallet() },
+        VaultState::Receipt { wallet: wallet() },
+    ]
+}
+
+fn all_events() -> Vec<VaultEvent> {
+    vec![
+        VaultEvent::UnsealStarted,
+        VaultEvent::UnsealFailed,
+        VaultEvent::KeyUnsealed,
+        VaultEvent::WalletLinked(wallet()),
+        VaultEvent::WalletLinked(String::new()),
+        VaultEvent::ScanStarted,
+        VaultEvent::ScanFailed,
+        VaultEvent::IdentityVerified,
+        VaultEvent::Revealed,
+        VaultEvent::IntentSealed,
+        VaultEvent::ReceiptDismissed,
+        VaultEvent::WalletUnlinked,
+        VaultEvent::WalletChanged(wallet()),
+        VaultEvent::WalletChanged(other_wallet()),
+        VaultEvent::WalletChanged(String::new()),
+    ]
+}
+
+/// The complete transition table; every pair not listed here must be rejected.
+fn allowed() -> Vec<(VaultState, VaultEvent, VaultState)> {
+    let mut table = vec![
+        (
+            VaultState::KeySealed { unsealing: false },
+            VaultEvent::UnsealStarted,
+            VaultState::KeySealed { unsealing: true },
+        ),
+        (
+            VaultState::KeySealed { unsealing: true },
+            VaultEvent::UnsealFailed,
+            VaultState::KeySealed { unsealing: false },
+        ),
+        (VaultState::KeySealed { unsealing: true }, VaultEvent::KeyUnsealed, VaultState::AwaitingWallet),
+        (
+            VaultState::AwaitingWallet,
+            VaultEvent::WalletLinked(wallet()),
+            VaultState::AwaitingBiometric { wallet: wallet(), scanning: false },
+        ),
+        (
+            VaultState::AwaitingBiometric { wallet: wallet(), scanning: false },
+            VaultEvent::ScanStarted,
+            VaultState::AwaitingBiometric { wallet: wallet(), scanning: true },
+        ),
+        (
+            VaultState::AwaitingBiometric { wallet: wallet(), scanning: true },
+            VaultEvent::ScanFailed,
+            VaultState::AwaitingBiometric { wallet: wallet(), scanning: false },
+        ),
+        (
+            VaultState::AwaitingBiometric { wallet: wallet(), scanning: true },
+            VaultEvent::IdentityVerified,
+            VaultState::Locked { wallet: wallet() },
+        ),
+        (VaultState::Locked { wallet: wallet() }, VaultEvent::Revealed, VaultState::Unlocked { wallet: wallet() }),
+        (
+            VaultState::Unlocked { wallet: wallet() },
+            VaultEvent::IntentSealed,
+            VaultState::Receipt { wallet: wallet() },
+        ),
+        (
+            VaultState::Receipt { wallet: wallet() },
+            VaultEvent::ReceiptDismissed,
+            VaultState::Unlocked { wallet: wallet() },
+        ),
+    ];
+    // Unlinking, or switching to another account, leaves any linked state.
+    for state in linked_states() {
+        table.push((state.clone(), VaultEvent::WalletUnlinked, VaultState::AwaitingWallet));
+        table.push((
+            state,
+            VaultEvent::WalletChanged(other_wallet()),
+            VaultState::AwaitingBiometric { wallet: other_wallet(), scanning: false },
+        ));
+    }
+    table
+}
+
+#[test]
+fn transition_table_is_exhaustive() {
+    let table = allowed();
+    for state in all_states() {
+        for event in all_events() {
+            let expected = table
+                .iter()
+                .find(|(from, on, _)| *from == state && *on == event)
+                .map(|(_, _, to)| to.clone());
+            assert_eq!(state.transition(&event), expected, "{:?} on {:?}", state, event);
+        }
+    }
+}
+
+#[test]
+fn happy_path_reaches_receipt_and_back() {
+    let mut state = VaultState::default();
+    assert_eq!(state, VaultState::KeySealed { unsealing: false });
+
+    for event in [
+        VaultEvent::UnsealStarted,
+        VaultEvent::KeyUnsealed,
+        VaultEvent::WalletLinked(wallet()),
+        VaultEvent::ScanStarted,
+        VaultEvent::IdentityVerified,
+        VaultEvent::Revealed,
+        VaultEvent::IntentSealed,
+    ] {
+        state.apply(event).unwrap();
+    }
+    assert_eq!(state, VaultState::Receipt { wallet: wallet() });
+
+    state.apply(VaultEvent::ReceiptDismissed).unwrap();
+    assert_eq!(state, VaultState::Unlocked { wallet: wallet() });
+}
+
+#[test]
+fn rejected_event_leaves_state_untouched() {
+    let mut state = VaultState::Locked { wallet: wallet() };
+    let err = state.apply(VaultEvent::IntentSealed).unwrap_err();
+
+    assert_eq!(
+        err,
+        InvalidTransition { state: VaultState::Locked { wallet: wallet() }, event: VaultEvent::IntentSealed }
+    );
+    assert_eq!(state, VaultState::Locked { wallet: wallet() });
+}
+
+#[test]
+fn step_indicators_follow_progress() {
+    let expected = [
+        // (key_ready, wallet_linked, identity_verified, unlocked)
+        (false, false, false, false),
+        (false, false, false, false),
+        (true, false, false, false),
+        (true, true, false, false),
+        (true, true, false, false),
+        (true, true, true, false),
+        (true, true, true, true),
+        (true, true, true, true),
+    ];
+    for (state, flags) in all_states().into_iter().zip(expected) {
+        let actual = (state.key_ready(), state.wallet_linked(), state.identity_verified(), state.unlocked());
+        assert_eq!(actual, flags, "{:?}", state);
+        assert_eq!(state.wallet().is_some(), flags.1, "{:?}", state);
+    }
+}
+
+#[test]
+fn account_switch_drops_verified_identity() {
+    let mut state = VaultState::Unlocked { wallet: wallet() };
+    state.apply(VaultEvent::WalletChanged(other_wallet())).unwrap();
+    assert_eq!(state.wallet(), Some(OTHER_WALLET));
+    assert!(!state.identity_verified());
+
+    // Re-announcing the same account changes nothing.
+    let mut same = VaultState::Locked { wallet: wallet() };
+    assert!(same.apply(VaultEvent::WalletChanged(wallet())).is_err());
+    assert_eq!(same, VaultState::Locked { wallet: wallet() });
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use ed25519_dalek::{Signer, SigningKey};
+use vext_vault::attestation::AttestationBody;
+use vext_vault::keys::DeviceKey;
+use vext_vault::verify::{verify_attestation, Policy, VerifyError};
+use vext_vault::IntentAttestation;
+
+use common::{body, seal, NOW};
+
+fn wallet(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32])
+}
+
+fn sealed(device: &DeviceKey, wallet_pubkey: &SigningKey) -> IntentAttestation {
+    let wallet_pubkey = bs58::encode(wallet_pubkey.verifying_key().to_bytes()).into_string();
+    seal(device, AttestationBody { wallet_pubkey, ..body(0) })
+}
+
+fn cosign(att: IntentAttestation, signer: &SigningKey) -> IntentAttestation {
+    let message = att.body.signing_message().unwrap();
+    att.with_wallet_signature(&signer.sign(&message).to_bytes())
+}
+
+#[test]
+fn accepts_wallet_cosignature_over_canonical_body() {
+    let device = DeviceKey::generate();
+    let att = cosign(sealed(&device, &wallet(7)), &wallet(7));
+
+    let ok = verify_attestation(&att, &device.verifying_key(), &Policy::new(NOW)).unwrap();
+    assert!(ok.wallet_cosigned);
+}
+
+#[test]
+fn rejects_signature_by_another_wallet() {
+    let device = DeviceKey::generate();
+    let att = cosign(sealed(&device, &wallet(7)), &wallet(8));
+
+    let err = verify_attestation(&att, &device.verifying_key(), &Policy::new(NOW)).unwrap_err();
+    assert_eq!(err, VerifyError::BadWalletSignature);
+}
+
+#[test]
+fn rejects_malformed_wallet_fields() {
+    let device = DeviceKey::generate();
+
+    let mut att = cosign(sealed(&device, &wallet(7)), &wallet(7));
+    att.wallet_signature = Some("0OIl".into()); // Not in the base58 alphabet
+    let err = verify_attestation(&att, &device.verifying_key(), &Policy::new(NOW)).unwrap_err();
+    assert_eq!(err, VerifyError::MalformedWalletSignature);
+
+    let mut body = sealed(&device, &wallet(7)).body;
+    body.wallet_pubkey = "not-a-wallet".into();
+    let att = IntentAttestation::seal(body, &device).unwrap().with_wallet_signature(&[0; 64]);
+    let err = verify_attestation(&att, &device.verifying_key(), &Policy::new(NOW)).unwrap_err();
+    assert_eq!(err, VerifyError::MalformedWalletKey);
+}
+
+#[test]
+fn missing_cosignature_only_fails_when_required() {
+    let device = DeviceKey::generate();
+    let att = sealed(&device, &wallet(7));
+
+    let ok = verify_attestation(&att, &device.verifying_key(), &Policy::new(NOW)).unwrap();
+    assert!(!ok.wallet_cosigned);
+
+    let strict = Policy { require_wallet_signature: true, ..Policy::new(NOW) };
+    let err = verify_attestation(&att, &device.verifying_key(), &strict).unwrap_err();
+    assert_eq!(err, VerifyError::MissingWalletSignature);
+}
+
+#[test]
+fn wallet_signature_survives_storage_and_is_omitted_when_absent() {
+    let device = DeviceKey::generate();
+    let unsigned = sealed(&device, &wallet(7));
+    assert!(!serde_json::to_string(&unsigned).unwrap().contains("wallet_signature"));
+
+    let cosigned = cosign(unsigned, &wallet(7));
+    let stored = serde_json::to_string(&cosigned).unwrap();
+    let loaded: IntentAttestation = serde_json::from_str(&stored).unwrap();
+    assert_eq!(loaded, cosigned);
+}
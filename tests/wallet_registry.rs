@@ -0,0 +1,87 @@
+use vext_vault::wallet::{
+    WalletError, WalletInfo, WalletRegistry, WalletSource, CODE_UNAUTHORIZED, CODE_USER_REJECTED, FEATURE_CONNECT,
+    FEATURE_DISCONNECT, FEATURE_SIGN_MESSAGE, FEATURE_SIGN_TYPED_DATA,
+};
+
+fn wallet(name: &str, source: WalletSource, features: &[&str], chains: &[&str]) -> WalletInfo {
+    WalletInfo {
+        name: name.into(),
+        icon: None,
+        chains: chains.iter().map(|c| c.to_string()).collect(),
+        features: features.iter().map(|f| f.to_string()).collect(),
+        source,
+    }
+}
+
+fn solana(name: &str, source: WalletSource) -> WalletInfo {
+    wallet(name, source, &[FEATURE_CONNECT, FEATURE_DISCONNECT, FEATURE_SIGN_MESSAGE], &["solana:mainnet"])
+}
+
+#[test]
+fn usable_wallets_need_connect_sign_message_and_a_solana_chain() {
+    assert!(solana("Phantom", WalletSource::Standard).is_usable());
+    assert!(!wallet("NoSign", WalletSource::Standard, &[FEATURE_CONNECT], &["solana:mainnet"]).is_usable());
+    assert!(!wallet("EvmOnly", WalletSource::Standard, &[FEATURE_CONNECT, FEATURE_SIGN_MESSAGE], &["eip155:1"]).is_usable());
+
+    let mut registry = WalletRegistry::default();
+    registry.register(solana("Phantom", WalletSource::Standard), 1);
+    registry.register(wallet("EvmOnly", WalletSource::Standard, &[FEATURE_CONNECT], &["eip155:1"]), 2);
+    registry.register(solana("Solflare", WalletSource::Standard), 3);
+
+    let names: Vec<String> = registry.usable().into_iter().map(|w| w.name).collect();
+    assert_eq!(names, ["Phantom", "Solflare"]);
+    assert_eq!(registry.get("Solflare"), Some(&3));
+    assert_eq!(registry.get("EvmOnly"), None);
+}
+
+#[test]
+fn standard_registration_supersedes_injected_fallback() {
+    let mut registry = WalletRegistry::default();
+    assert!(registry.register(solana("Phantom", WalletSource::Injected), "window.solana"));
+    assert!(registry.register(solana("Phantom", WalletSource::Standard), "standard"));
+    assert_eq!(registry.get("Phantom"), Some(&"standard"));
+
+    // The fallback never displaces a Standard wallet, and duplicates are ignored.
+    assert!(!registry.register(solana("Phantom", WalletSource::Injected), "window.solana"));
+    assert!(!registry.register(solana("Phantom", WalletSource::Standard), "again"));
+    assert_eq!(registry.get("Phantom"), Some(&"standard"));
+    assert_eq!(registry.usable().len(), 1);
+}
+
+#[test]
+fn evm_wallet_is_listed_once_its_chain_is_known() {
+    let mut registry = WalletRegistry::default();
+    let features = [FEATURE_CONNECT, FEATURE_SIGN_TYPED_DATA];
+    registry.register(wallet("MetaMask", WalletSource::Eip1193, &features, &[]), ());
+    assert!(registry.usable().is_empty());
+
+    assert!(registry.set_chains("MetaMask", vec!["eip155:10".into()]));
+    assert_eq!(registry.usable()[0].chains, ["eip155:10"]);
+    assert!(!registry.set_chains("MetaMask", vec!["eip155:10".into()]));
+    assert!(!registry.set_chains("Unknown", vec!["eip155:1".into()]));
+}
+
+#[test]
+fn unregister_removes_by_name() {
+    let mut registry = WalletRegistry::default();
+    registry.register(solana("Phantom", WalletSource::Standard), ());
+    assert!(registry.unregister("Phantom"));
+    assert!(!registry.unregister("Phantom"));
+    assert!(registry.usable().is_empty());
+}
+
+#[test]
+fn classifies_wallet_rejections() {
+    let cases = [
+        (Some(CODE_USER_REJECTED), "", WalletError::UserRejected),
+        (Some(CODE_UNAUTHORIZED), "", WalletError::Locked),
+        (None, "User rejected the request.", WalletError::UserRejected),
+        (Some(-32603), "MetaMask: User denied account authorization", WalletError::UserRejected),
+        (None, "Wallet is locked", WalletError::Locked),
+        (Some(-32603), "Internal JSON-RPC error", WalletError::Failed("Internal JSON-RPC error".into())),
+        (None, "", WalletError::Failed("request failed".into())),
+    ];
+    for (code, message, expected) in cases {
+        assert_eq!(WalletError::from_rejection(code, message), expected, "{:?} {:?}", code, message);
+    }
+}
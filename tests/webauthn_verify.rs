@@ -0,0 +1,206 @@
+mod common;
+
+use ciborium::Value;
+use futures::executor::block_on;
+use vext_vault::attestation::AttestationBody;
+use vext_vault::biometric::{BiometricProvider, MockBiometric, FLAG_USER_PRESENT, FLAG_USER_VERIFIED};
+use vext_vault::keys::DeviceKey;
+use vext_vault::verify::{verify_attestation, Policy, VerifyError};
+use vext_vault::webauthn::{
+    decode_cose_key, encode_cose_key, parse_attestation_object, AssertionError, CredentialRecord, RegisteredCredential,
+};
+use vext_vault::IntentAttestation;
+
+use common::NOW;
+
+const RP_ID: &str = "vault.vext.com";
+const ORIGIN: &str = "https://vault.vext.com";
+
+fn mock() -> MockBiometric {
+    MockBiometric::from_seed(b"device-1").with_origin(RP_ID, ORIGIN)
+}
+
+fn credential(mock: &MockBiometric) -> RegisteredCredential {
+    let record = CredentialRecord::new(mock.credential_id(), &encode_cose_key(&mock.verifying_key()));
+    record.register(RP_ID).unwrap()
+}
+
+fn body(key: &DeviceKey) -> AttestationBody {
+    AttestationBody {
+        biometric_proof: String::new(),
+        hold_started_ms: Some(NOW * 1000 - 2000),
+        hold_ended_ms: Some(NOW * 1000 - 400),
+        signer_pubkey: key.public_key_hex(),
+        ..common::body(0)
+    }
+}
+
+/// Asserts over `challenge_body`'s hash, then seals `body` with that proof.
+fn seal_with_proof(key: &DeviceKey, provider: &MockBiometric, challenge_body: &AttestationBody, mut body: AttestationBody) -> IntentAttestation {
+    let challenge = challenge_body.biometric_challenge().unwrap();
+    body.biometric_proof = block_on(provider.verify_user(&challenge)).unwrap().to_proof();
+    IntentAttestation::seal(body, key).unwrap()
+}
+
+fn policy(credential: RegisteredCredential) -> Policy {
+    Policy { credential: Some(credential), ..Policy::new(NOW) }
+}
+
+fn biometric_error(result: Result<vext_vault::Verified, VerifyError>) -> AssertionError {
+    match result {
+        Err(VerifyError::Biometric(e)) => e,
+        other => panic!("expected a biometric rejection, got {:?}", other),
+    }
+}
+
+#[test]
+fn accepts_assertion_over_the_body_hash() {
+    let key = DeviceKey::generate();
+    let provider = mock();
+    let att = seal_with_proof(&key, &provider, &body(&key), body(&key));
+
+    let ok = verify_attestation(&att, &key.verifying_key(), &policy(credential(&provider))).unwrap();
+    assert_eq!(ok.sign_count, Some(1));
+
+    // Without a credential in the policy the proof is not inspected.
+    let ok = verify_attestation(&att, &key.verifying_key(), &Policy::new(NOW)).unwrap();
+    assert_eq!(ok.sign_count, None);
+}
+
+#[test]
+fn challenge_ignores_only_the_proof_field() {
+    let key = DeviceKey::generate();
+    let mut with_proof = body(&key);
+    with_proof.biometric_proof = "anything".into();
+    assert_eq!(with_proof.biometric_challenge(), body(&key).biometric_challenge());
+
+    let mut other_asset = body(&key);
+    other_asset.asset_symbol = "BTC".into();
+    assert_ne!(other_asset.biometric_challenge(), body(&key).biometric_challenge());
+}
+
+#[test]
+fn rejects_assertion_made_for_another_body() {
+    let key = DeviceKey::generate();
+    let provider = mock();
+    let mut other = body(&key);
+    other.asset_symbol = "BTC".into();
+    let att = seal_with_proof(&key, &provider, &other, body(&key));
+
+    let err = biometric_error(verify_attestation(&att, &key.verifying_key(), &policy(credential(&provider))));
+    assert_eq!(err, AssertionError::ChallengeMismatch);
+}
+
+#[test]
+fn rejects_wrong_relying_party_and_origin() {
+    let key = DeviceKey::generate();
+
+    let elsewhere = MockBiometric::from_seed(b"device-1").with_origin("evil.example", ORIGIN);
+    let att = seal_with_proof(&key, &elsewhere, &body(&key), body(&key));
+    let err = biometric_error(verify_attestation(&att, &key.verifying_key(), &policy(credential(&elsewhere))));
+    assert_eq!(err, AssertionError::RpIdMismatch);
+
+    let phished = MockBiometric::from_seed(b"device-1").with_origin(RP_ID, "https://vault-vext.example");
+    let att = seal_with_proof(&key, &phished, &body(&key), body(&key));
+    let err = biometric_error(verify_attestation(&att, &key.verifying_key(), &policy(credential(&phished))));
+    assert_eq!(err, AssertionError::OriginNotAllowed("https://vault-vext.example".into()));
+}
+
+#[test]
+fn requires_presence_and_verification_flags() {
+    let key = DeviceKey::generate();
+    let cases = [
+        (FLAG_USER_VERIFIED, AssertionError::UserNotPresent),
+        (FLAG_USER_PRESENT, AssertionError::UserNotVerified),
+    ];
+    for (flags, expected) in cases {
+        let provider = mock().with_flags(flags);
+        let att = seal_with_proof(&key, &provider, &body(&key), body(&key));
+        let err = biometric_error(verify_attestation(&att, &key.verifying_key(), &policy(credential(&provider))));
+        assert_eq!(err, expected);
+    }
+}
+
+#[test]
+fn rejects_unknown_credential_and_foreign_signature() {
+    let key = DeviceKey::generate();
+    let provider = mock();
+    let att = seal_with_proof(&key, &provider, &body(&key), body(&key));
+
+    let stranger = MockBiometric::from_seed(b"device-2");
+    let err = biometric_error(verify_attestation(&att, &key.verifying_key(), &policy(credential(&stranger))));
+    assert_eq!(err, AssertionError::UnknownCredential);
+
+    // Right credential id, wrong public key.
+    let mut impostor = credential(&provider);
+    impostor.public_key = stranger.verifying_key();
+    let err = biometric_error(verify_attestation(&att, &key.verifying_key(), &policy(impostor)));
+    assert_eq!(err, AssertionError::BadSignature);
+}
+
+#[test]
+fn sign_count_must_advance() {
+    let key = DeviceKey::generate();
+    let provider = mock();
+    let first = seal_with_proof(&key, &provider, &body(&key), body(&key));
+    let second = seal_with_proof(&key, &provider, &body(&key), body(&key));
+
+    let mut stored = credential(&provider);
+    let ok = verify_attestation(&second, &key.verifying_key(), &policy(stored.clone())).unwrap();
+    stored.sign_count = ok.sign_count.unwrap();
+
+    let err = biometric_error(verify_attestation(&first, &key.verifying_key(), &policy(stored)));
+    assert_eq!(err, AssertionError::SignCountRegressed { stored: 2, found: 1 });
+}
+
+#[test]
+fn rejects_legacy_marker_when_a_credential_is_required() {
+    let key = DeviceKey::generate();
+    let mut legacy = body(&key);
+    legacy.biometric_proof = "BIO-ATTESTED".into();
+    let att = IntentAttestation::seal(legacy, &key).unwrap();
+
+    let err = biometric_error(verify_attestation(&att, &key.verifying_key(), &policy(credential(&mock()))));
+    assert!(matches!(err, AssertionError::Malformed(_)));
+}
+
+#[test]
+fn cose_key_round_trips_and_rejects_other_algorithms() {
+    let key = mock().verifying_key();
+    assert_eq!(decode_cose_key(&encode_cose_key(&key)).unwrap(), key);
+
+    let mut eddsa = Vec::new();
+    let map = Value::Map(vec![
+        (Value::from(1), Value::from(1)),  // kty: OKP
+        (Value::from(3), Value::from(-8)), // alg: EdDSA
+        (Value::from(-1), Value::from(6)), // crv: Ed25519
+        (Value::from(-2), Value::Bytes(vec![0; 32])),
+    ]);
+    ciborium::ser::into_writer(&map, &mut eddsa).unwrap();
+    assert!(matches!(decode_cose_key(&eddsa), Err(AssertionError::UnsupportedKey(_))));
+}
+
+#[test]
+fn extracts_credential_from_attestation_object() {
+    let provider = mock();
+    let cose = encode_cose_key(&provider.verifying_key());
+    let id = provider.credential_id();
+
+    let mut auth_data = vec![0u8; 32];
+    auth_data.push(FLAG_USER_PRESENT | FLAG_USER_VERIFIED | 0x40);
+    auth_data.extend_from_slice(&0u32.to_be_bytes());
+    auth_data.extend_from_slice(&[0u8; 16]); // aaguid
+    auth_data.extend_from_slice(&(id.len() as u16).to_be_bytes());
+    auth_data.extend_from_slice(id);
+    auth_data.extend_from_slice(&cose);
+
+    let object = Value::Map(vec![
+        (Value::from("fmt"), Value::from("none")),
+        (Value::from("attStmt"), Value::Map(vec![])),
+        (Value::from("authData"), Value::Bytes(auth_data)),
+    ]);
+    let mut raw = Vec::new();
+    ciborium::ser::into_writer(&object, &mut raw).unwrap();
+
+    assert_eq!(parse_attestation_object(&raw).unwrap(), (id.to_vec(), cose));
+}